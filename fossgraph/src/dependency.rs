@@ -1,4 +1,7 @@
 pub mod normalize;
+pub mod scan;
+
+use normalize::go;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Dependency {
@@ -24,25 +27,198 @@ pub enum Dependency {
         artifact_id: String,
         version: String,
     },
+    Cargo {
+        name: String,
+        version: String,
+    },
+    GoModule {
+        path: String,
+        version: String,
+    },
+    RubyGem {
+        name: String,
+        version: String,
+        platform: Option<String>,
+    },
+    PyPI {
+        name: String, // normalized per PEP 503 by `canonicalize`
+        version: String,
+    },
+    Packagist {
+        name: String, // <vendor>/<name>
+        version: String,
+    },
+    Pub {
+        name: String,
+        version: String,
+    },
+    NuGet {
+        id: String,
+        version: String,
+    },
+    Hex {
+        name: String,
+        version: String,
+        repo: String, // e.g. hexpm, hexpm:<organization>
+    },
+    Jsr {
+        scope: String, // without leading `@`
+        name: String,
+        version: String,
+    },
+    Url {
+        url: String,
+        hash: Option<String>, // in the form the lockfile has (e.g. sha256 hex, SRI)
+    },
+    Binary {
+        url: String, // specification of prebuilt, possibly closed-source artifacts
+        version: String,
+    },
+    Bazel {
+        name: String, // module name in the Bazel Central Registry
+        version: String,
+    },
+    HelmChart {
+        name: String,
+        version: String,
+        repository: String, // URL of chart repository, or `oci://` registry
+    },
+    TerraformProvider {
+        source: String, // <hostname>/<namespace>/<type>
+        version: String,
+    },
 }
 
 impl Dependency {
     pub fn canonicalize(&self) -> Self {
         match self {
             Self::Git { url, head } => {
-                if let Some(substr) = url.strip_prefix("git@github.com:") {
-                    let (owner, substr) = substr.split_once('/').unwrap();
-                    let (name, _) = substr.split_once(".git").unwrap();
-                    return Self::GitHub {
-                        owner: owner.into(),
-                        name: name.into(),
-                        head: head.to_owned(),
-                    };
+                // e.g. `git+ssh://git@github.com/owner/name.git` -> `git@github.com:owner/name.git`
+                let url = url.strip_prefix("git+").unwrap_or(url);
+                // scp-like syntax can't have a port, so `ssh://git@host:2222/owner/name.git` is kept
+                let url = match url.strip_prefix("ssh://") {
+                    Some(substr) => match substr.split_once('/') {
+                        Some((authority, path))
+                            if !authority.rsplit('@').next().unwrap().contains(':') =>
+                        {
+                            format!("{authority}:{path}")
+                        }
+                        _ => url.to_owned(),
+                    },
+                    None => url.to_owned(),
+                };
+
+                let github_path = [
+                    "git@github.com:",
+                    "https://github.com/",
+                    "git://github.com/",
+                ]
+                .iter()
+                .find_map(|prefix| url.strip_prefix(prefix));
+                if let Some(substr) = github_path {
+                    if let Some((owner, name)) = substr.split_once('/') {
+                        let name = name.trim_end_matches('/');
+                        let name = name.strip_suffix(".git").unwrap_or(name);
+                        return Self::GitHub {
+                            owner: owner.into(),
+                            name: name.into(),
+                            head: head.to_owned(),
+                        };
+                    }
                 }
 
-                self.clone()
+                Self::Git {
+                    url,
+                    head: head.to_owned(),
+                }
+            }
+            Self::GoModule { path, version } => {
+                let Some(substr) = path.strip_prefix("github.com/") else {
+                    return self.clone();
+                };
+                let segments: Vec<&str> = substr.split('/').collect();
+                let [owner, name, subdir @ ..] = segments.as_slice() else {
+                    return self.clone();
+                };
+                // e.g. `github.com/owner/name/v2`
+                let subdir = match subdir {
+                    [rest @ .., major]
+                        if major.len() > 1
+                            && major.starts_with('v')
+                            && major[1..].bytes().all(|byte| byte.is_ascii_digit()) =>
+                    {
+                        rest
+                    }
+                    _ => subdir,
+                };
+                let version = version.strip_suffix("+incompatible").unwrap_or(version);
+                // nested modules are tagged with their directory (e.g. `sub/v1.0.0`)
+                let head = match go::commit_hash(version) {
+                    Some(commit_hash) => commit_hash.to_owned(),
+                    None if subdir.is_empty() => version.to_owned(),
+                    None => format!("{}/{version}", subdir.join("/")),
+                };
+                Self::GitHub {
+                    owner: (*owner).into(),
+                    name: (*name).into(),
+                    head: Some(head),
+                }
+            }
+            Self::PyPI { name, version } => {
+                // e.g. `Foo.Bar_baz` -> `foo-bar-baz`
+                let mut normalized = String::with_capacity(name.len());
+                for c in name.chars() {
+                    if matches!(c, '-' | '_' | '.') {
+                        if !normalized.ends_with('-') {
+                            normalized.push('-');
+                        }
+                    } else {
+                        normalized.push(c.to_ascii_lowercase());
+                    }
+                }
+                Self::PyPI {
+                    name: normalized,
+                    version: version.to_owned(),
+                }
             }
             _ => self.clone(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn git(url: &str) -> Dependency {
+        Dependency::Git {
+            url: url.into(),
+            head: None,
+        }
+    }
+
+    #[test]
+    fn test_canonicalize() {
+        let github = Dependency::GitHub {
+            owner: "daangn".into(),
+            name: "cjk-slug".into(),
+            head: None,
+        };
+        assert_eq!(
+            git("git+ssh://git@github.com/daangn/cjk-slug.git").canonicalize(),
+            github,
+        );
+        assert_eq!(
+            git("https://github.com/daangn/cjk-slug").canonicalize(),
+            github,
+        );
+        assert_eq!(
+            git("ssh://git@git.example.com/team/repo.git").canonicalize(),
+            git("git@git.example.com:team/repo.git"),
+        );
+        assert_eq!(
+            git("git+ssh://git@git.example.com:2222/team/repo.git").canonicalize(),
+            git("ssh://git@git.example.com:2222/team/repo.git"),
+        );
+    }
+}
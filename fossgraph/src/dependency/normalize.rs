@@ -1 +1,335 @@
+pub mod bazel;
+pub mod bun;
+pub mod bundler;
+pub mod cargo;
+pub mod carthage;
+pub mod cocoapods;
+pub mod composer;
+pub mod deno;
+pub mod go;
+pub mod gradle;
+pub mod helm;
+pub mod maven;
+pub mod maven_install;
+pub mod mix;
+pub mod nix;
+pub mod npm;
+pub mod nuget;
+pub mod pip;
+pub mod pipenv;
+pub mod pnpm;
+pub mod poetry;
+pub mod pub_dev;
+pub mod swiftpm;
+pub mod terraform;
+pub mod uv;
 pub mod yarn_berry;
+pub mod yarn_v1;
+
+use std::collections::HashSet;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use crate::dependency::Dependency;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Couldn't read the lockfile.\n{0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Unknown lockfile: {path}")]
+    UnknownFormat { path: String },
+
+    #[error(transparent)]
+    Bazel(#[from] bazel::Error),
+    #[error(transparent)]
+    Bun(#[from] bun::Error),
+    #[error(transparent)]
+    Bundler(#[from] bundler::Error),
+    #[error(transparent)]
+    Cargo(#[from] cargo::Error),
+    #[error(transparent)]
+    Carthage(#[from] carthage::Error),
+    #[error(transparent)]
+    CocoaPods(#[from] cocoapods::Error),
+    #[error(transparent)]
+    Composer(#[from] composer::Error),
+    #[error(transparent)]
+    Deno(#[from] deno::Error),
+    #[error(transparent)]
+    Go(#[from] go::Error),
+    #[error(transparent)]
+    Gradle(#[from] gradle::Error),
+    #[error(transparent)]
+    Helm(#[from] helm::Error),
+    #[error(transparent)]
+    MavenInstall(#[from] maven_install::Error),
+    #[error(transparent)]
+    Mix(#[from] mix::Error),
+    #[error(transparent)]
+    Nix(#[from] nix::Error),
+    #[error(transparent)]
+    Npm(#[from] npm::Error),
+    #[error(transparent)]
+    NuGet(#[from] nuget::Error),
+    #[error(transparent)]
+    Pip(#[from] pip::Error),
+    #[error(transparent)]
+    Pipenv(#[from] pipenv::Error),
+    #[error(transparent)]
+    Pnpm(#[from] pnpm::Error),
+    #[error(transparent)]
+    Poetry(#[from] poetry::Error),
+    #[error(transparent)]
+    Pub(#[from] pub_dev::Error),
+    #[error(transparent)]
+    SwiftPM(#[from] swiftpm::Error),
+    #[error(transparent)]
+    Terraform(#[from] terraform::Error),
+    #[error(transparent)]
+    Uv(#[from] uv::Error),
+    #[error(transparent)]
+    YarnBerry(#[from] yarn_berry::Error),
+    #[error(transparent)]
+    YarnV1(#[from] yarn_v1::Error),
+}
+
+/// Lockfile formats, one for each normalizer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Format {
+    Bazel,
+    Bun,
+    Bundler,
+    Cargo,
+    Carthage,
+    CocoaPods,
+    Composer,
+    Deno,
+    Go,
+    Gradle,
+    Helm,
+    MavenInstall,
+    Mix,
+    Nix,
+    Npm,
+    NuGet,
+    Pip,
+    Pipenv,
+    Pnpm,
+    Poetry,
+    Pub,
+    SwiftPM,
+    Terraform,
+    Uv,
+    YarnBerry,
+    YarnV1,
+}
+
+impl Format {
+    /// Formats the file name can have. Some lockfiles share the same name (e.g. `yarn.lock`).
+    fn candidates(path: &Path) -> &'static [Self] {
+        let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+            return &[];
+        };
+        match file_name {
+            "MODULE.bazel.lock" => &[Self::Bazel],
+            "bun.lock" => &[Self::Bun],
+            "Gemfile.lock" | "gems.locked" => &[Self::Bundler],
+            "Cargo.lock" => &[Self::Cargo],
+            "Cartfile.resolved" => &[Self::Carthage],
+            "Podfile.lock" => &[Self::CocoaPods],
+            "composer.lock" => &[Self::Composer],
+            "deno.lock" => &[Self::Deno],
+            "go.sum" => &[Self::Go],
+            "gradle.lockfile" | "buildscript-gradle.lockfile" => &[Self::Gradle],
+            "Chart.lock" => &[Self::Helm],
+            "maven_install.json" => &[Self::MavenInstall],
+            "mix.lock" => &[Self::Mix],
+            "flake.lock" => &[Self::Nix],
+            "package-lock.json" | "npm-shrinkwrap.json" => &[Self::Npm],
+            "packages.lock.json" => &[Self::NuGet],
+            "Pipfile.lock" => &[Self::Pipenv],
+            "pnpm-lock.yaml" => &[Self::Pnpm],
+            "poetry.lock" => &[Self::Poetry],
+            "pubspec.lock" => &[Self::Pub],
+            "Package.resolved" => &[Self::SwiftPM],
+            ".terraform.lock.hcl" => &[Self::Terraform],
+            "uv.lock" => &[Self::Uv],
+            "yarn.lock" => &[Self::YarnBerry, Self::YarnV1],
+            _ if file_name.starts_with("requirements") && file_name.ends_with(".txt") => {
+                &[Self::Pip]
+            }
+            // legacy per-configuration lockfiles (`gradle/dependency-locks/<configuration>.lockfile`)
+            _ if file_name.ends_with(".lockfile")
+                && path.parent().and_then(Path::file_name) == Some("dependency-locks".as_ref()) =>
+            {
+                &[Self::Gradle]
+            }
+            _ => &[],
+        }
+    }
+
+    /// Whether the content has the signature of the format, if the format has one.
+    fn matches(self, content: &str) -> bool {
+        match self {
+            Self::YarnBerry => content.lines().any(|line| line.starts_with("__metadata:")),
+            Self::YarnV1 => content.lines().any(|line| line == "# yarn lockfile v1"),
+            Self::Npm => content.contains("\"lockfileVersion\""),
+            Self::Pnpm => content
+                .lines()
+                .any(|line| line.starts_with("lockfileVersion:")),
+            _ => true,
+        }
+    }
+
+    /// Whether the path could be a lockfile of any format, without reading it.
+    pub fn is_lockfile(path: impl AsRef<Path>) -> bool {
+        !Self::candidates(path.as_ref()).is_empty()
+    }
+
+    /// Detects the format by the file name, and its content for the ambiguous ones.
+    pub fn detect(path: impl AsRef<Path>, content: &str) -> Option<Self> {
+        Self::candidates(path.as_ref())
+            .iter()
+            .copied()
+            .find(|format| format.matches(content))
+    }
+
+    pub fn normalize(self, content: &str) -> Result<HashSet<Dependency>, Error> {
+        let deps = match self {
+            Self::Bazel => bazel::normalize(content)?,
+            Self::Bun => bun::normalize(content)?,
+            Self::Bundler => bundler::normalize(content)?,
+            Self::Cargo => cargo::normalize(content)?,
+            Self::Carthage => carthage::normalize(content)?,
+            Self::CocoaPods => cocoapods::normalize(content)?,
+            Self::Composer => composer::normalize(content)?,
+            Self::Deno => deno::normalize(content)?,
+            Self::Go => go::normalize(content)?,
+            Self::Gradle => gradle::normalize(content)?,
+            Self::Helm => helm::normalize(content)?,
+            Self::MavenInstall => maven_install::normalize(content)?,
+            Self::Mix => mix::normalize(content)?,
+            Self::Nix => nix::normalize(content)?,
+            Self::Npm => npm::normalize(content)?,
+            Self::NuGet => nuget::normalize(content)?,
+            Self::Pip => pip::normalize(content)?,
+            Self::Pipenv => pipenv::normalize(content)?,
+            Self::Pnpm => pnpm::normalize(content)?,
+            Self::Poetry => poetry::normalize(content)?,
+            Self::Pub => pub_dev::normalize(content)?,
+            Self::SwiftPM => swiftpm::normalize(content)?,
+            Self::Terraform => terraform::normalize(content)?,
+            Self::Uv => uv::normalize(content)?,
+            Self::YarnBerry => yarn_berry::normalize(content)?,
+            Self::YarnV1 => yarn_v1::normalize(content)?,
+        };
+        Ok(deps)
+    }
+}
+
+/// Reads the lockfile, and normalizes it by the detected format.
+///
+/// go.sum is cross-checked with go.mod next to it, if any.
+pub fn detect_and_normalize(
+    path: impl AsRef<Path>,
+) -> Result<(Format, HashSet<Dependency>), Error> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path)?;
+    let Some(format) = Format::detect(path, &content) else {
+        return Err(Error::UnknownFormat {
+            path: path.display().to_string(),
+        });
+    };
+    let deps = match format {
+        Format::Go => match fs::read_to_string(path.with_file_name("go.mod")) {
+            Ok(go_mod) => go::normalize_with_go_mod(&content, &go_mod)?,
+            Err(error) if error.kind() == ErrorKind::NotFound => format.normalize(&content)?,
+            Err(error) => return Err(error.into()),
+        },
+        _ => format.normalize(&content)?,
+    };
+    Ok((format, deps))
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        let berry = indoc! {r#"
+          # This file is generated by running "yarn install" inside your project.
+          # Manual changes might be lost - proceed with caution!
+
+          __metadata:
+            version: 6
+            cacheKey: 8
+        "#};
+        let v1 = indoc! {r#"
+          # THIS IS AN AUTOGENERATED FILE. DO NOT EDIT THIS FILE DIRECTLY.
+          # yarn lockfile v1
+        "#};
+
+        assert_eq!(
+            Format::detect("app/yarn.lock", berry),
+            Some(Format::YarnBerry)
+        );
+        assert_eq!(Format::detect("app/yarn.lock", v1), Some(Format::YarnV1));
+        assert_eq!(Format::detect("app/yarn.lock", ""), None);
+        assert_eq!(
+            Format::detect("package-lock.json", r#"{ "lockfileVersion": 3 }"#),
+            Some(Format::Npm),
+        );
+        assert_eq!(Format::detect("package-lock.json", "{}"), None);
+        assert_eq!(
+            Format::detect("pnpm-lock.yaml", "lockfileVersion: '9.0'\n"),
+            Some(Format::Pnpm),
+        );
+        assert_eq!(
+            Format::detect("requirements-dev.txt", ""),
+            Some(Format::Pip),
+        );
+        assert_eq!(
+            Format::detect("gradle/dependency-locks/compileClasspath.lockfile", ""),
+            Some(Format::Gradle),
+        );
+        assert_eq!(Format::detect("package.json", "{}"), None);
+    }
+
+    #[test]
+    fn test_detect_and_normalize_go() {
+        let root = std::env::temp_dir().join(format!("fossgraph-go-{}", std::process::id()));
+        let go_sum = indoc! {r#"
+          github.com/google/uuid v1.3.0 h1:...
+          github.com/google/uuid v1.3.0/go.mod h1:...
+          gopkg.in/yaml.v3 v3.0.0 h1:...
+        "#};
+        let go_mod = indoc! {r#"
+          module github.com/daangn/example
+
+          require github.com/google/uuid v1.3.0
+
+          exclude gopkg.in/yaml.v3 v3.0.0
+        "#};
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("go.sum"), go_sum).unwrap();
+        fs::write(root.join("go.mod"), go_mod).unwrap();
+
+        let result = detect_and_normalize(root.join("go.sum"));
+        fs::remove_dir_all(&root).unwrap();
+        assert_eq!(
+            result.unwrap(),
+            (
+                Format::Go,
+                HashSet::from([Dependency::GoModule {
+                    path: "github.com/google/uuid".into(),
+                    version: "v1.3.0".into(),
+                }]),
+            ),
+        );
+    }
+}
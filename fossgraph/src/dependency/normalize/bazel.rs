@@ -0,0 +1,348 @@
+use std::collections::HashSet;
+
+use crate::dependency::Dependency;
+
+use serde_json::{Map, Value};
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum Error {
+    #[error("Couldn't parse the lockfile.\n{message}")]
+    InvalidLockfileFormat { message: String },
+
+    #[error("Unsupported resolution: {resolution}")]
+    UnsupportedResolution { resolution: String },
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(_error: serde_json::Error) -> Self {
+        Self::invalid_json()
+    }
+}
+
+impl Error {
+    fn invalid_json() -> Self {
+        Self::InvalidLockfileFormat {
+            message: "Not a valid JSON".into(),
+        }
+    }
+
+    fn invalid_format() -> Self {
+        Self::InvalidLockfileFormat {
+            message: "Malformed lockfile".into(),
+        }
+    }
+
+    fn invalid_module(key: &str) -> Self {
+        Self::InvalidLockfileFormat {
+            message: format!("Some module has unsupported form: {key}"),
+        }
+    }
+}
+
+// Lockfile layout (MODULE.bazel.lock):
+//
+// Older versions (Bazel 7.0, 7.1) have the whole `moduleDepGraph`, which maps
+// `<name>@<version>` keys to `{ name, version, repoSpec, ... }`. The root module is `<root>`.
+//
+// Modules from non-registry overrides (`git_override`, `archive_override`, etc.) have empty version
+// (e.g. `rules_foo@_`), and their `repoSpec` is the repository rule fetching them.
+//
+// - `git_repository`: `attributes` has `remote` and `commit` (or `tag`)
+// - `http_archive`: `attributes` has `urls` (or `url`) and `integrity`
+//
+// Newer versions (Bazel 7.2+) only have `registryFileHashes`, which maps URLs of the registry files
+// (`<registry>/modules/<name>/<version>/{MODULE.bazel,source.json}`) to their hashes.
+// MODULE.bazel files are fetched for every version considered during the resolution,
+// but source.json files only for the selected ones.
+// Non-registry overrides are not in the lockfile anymore.
+
+const BCR_URLS: [&str; 2] = ["https://bcr.bazel.build", "https://bcr.cloud.google.com"];
+
+fn normalize_repo_spec(key: &str, repo_spec: &Map<String, Value>) -> Result<Dependency, Error> {
+    let rule = repo_spec
+        .get("ruleClassName")
+        .and_then(Value::as_str)
+        .ok_or_else(|| Error::invalid_module(key))?;
+    let attributes = repo_spec
+        .get("attributes")
+        .and_then(Value::as_object)
+        .ok_or_else(|| Error::invalid_module(key))?;
+    match rule {
+        "git_repository" => {
+            let remote = attributes
+                .get("remote")
+                .and_then(Value::as_str)
+                .ok_or_else(|| Error::invalid_module(key))?;
+            let head = ["commit", "tag"]
+                .iter()
+                .find_map(|key| attributes.get(*key).and_then(Value::as_str));
+            Ok(Dependency::Git {
+                url: remote.into(),
+                head: head.map(String::from),
+            }
+            .canonicalize())
+        }
+        "http_archive" => {
+            let url = attributes
+                .get("urls")
+                .and_then(|urls| urls.get(0))
+                .or_else(|| attributes.get("url"))
+                .and_then(Value::as_str)
+                .ok_or_else(|| Error::invalid_module(key))?;
+            let integrity = attributes.get("integrity").and_then(Value::as_str);
+            Ok(Dependency::Url {
+                url: url.into(),
+                hash: integrity.map(String::from),
+            })
+        }
+        // local repositories and built-in modules are not supported
+        _ => Err(Error::UnsupportedResolution {
+            resolution: key.into(),
+        }),
+    }
+}
+
+fn normalize_single_module(key: &str, module: &Map<String, Value>) -> Result<Dependency, Error> {
+    let name = module.get("name").and_then(Value::as_str);
+    let version = module.get("version").and_then(Value::as_str);
+    let (Some(name), Some(version)) = (name, version) else {
+        return Err(Error::invalid_module(key));
+    };
+    if !version.is_empty() {
+        return Ok(Dependency::Bazel {
+            name: name.into(),
+            version: version.into(),
+        });
+    }
+    match module.get("repoSpec").and_then(Value::as_object) {
+        Some(repo_spec) => normalize_repo_spec(key, repo_spec),
+        None => Err(Error::UnsupportedResolution {
+            resolution: key.into(),
+        }),
+    }
+}
+
+fn normalize_registry_file(url: &str) -> Result<Dependency, Error> {
+    // registry-wide files (e.g. `bazel_registry.json`)
+    let Some((registry, path)) = url.split_once("/modules/") else {
+        return Err(Error::UnsupportedResolution {
+            resolution: url.into(),
+        });
+    };
+    // private registry is not supported
+    if !BCR_URLS.contains(&registry) {
+        return Err(Error::UnsupportedResolution {
+            resolution: registry.into(),
+        });
+    }
+    match path.split('/').collect::<Vec<&str>>().as_slice() {
+        [name, version, "source.json"] => Ok(Dependency::Bazel {
+            name: (*name).into(),
+            version: (*version).into(),
+        }),
+        [_, _, "MODULE.bazel"] => Err(Error::UnsupportedResolution {
+            resolution: url.into(),
+        }),
+        _ => Err(Error::invalid_module(url)),
+    }
+}
+
+fn normalize_json(value: Value) -> Result<HashSet<Dependency>, Error> {
+    let mut deps: HashSet<Dependency> = HashSet::new();
+
+    let mut results: Vec<Result<Dependency, Error>> = Vec::new();
+    if let Some(modules) = value.get("moduleDepGraph") {
+        let Some(modules) = modules.as_object() else {
+            return Err(Error::invalid_format());
+        };
+        for (key, module) in modules {
+            if key == "<root>" {
+                continue;
+            }
+            let Some(module) = module.as_object() else {
+                return Err(Error::invalid_format());
+            };
+            results.push(normalize_single_module(key, module));
+        }
+    }
+    if let Some(files) = value.get("registryFileHashes") {
+        let Some(files) = files.as_object() else {
+            return Err(Error::invalid_format());
+        };
+        results.extend(files.keys().map(|url| normalize_registry_file(url)));
+    }
+    for result in results {
+        match result {
+            Ok(dependency) => {
+                deps.insert(dependency);
+            }
+            Err(Error::UnsupportedResolution { .. }) => {
+                // noop
+            }
+            Err(error) => {
+                return Err(error);
+            }
+        }
+    }
+    Ok(deps)
+}
+
+pub fn normalize(value: &str) -> Result<HashSet<Dependency>, Error> {
+    let json: Value = serde_json::from_str(value)?;
+    normalize_json(json)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn test_normalize_module_dep_graph() {
+        let lockfile = indoc! {r#"
+          {
+            "lockFileVersion": 3,
+            "moduleFileHash": "...",
+            "moduleDepGraph": {
+              "<root>": {
+                "name": "karrot",
+                "version": "",
+                "key": "<root>",
+                "repoName": "karrot",
+                "deps": {
+                  "rules_go": "rules_go@0.41.0",
+                  "karrot_proto": "karrot_proto@_",
+                  "rules_foo": "rules_foo@_"
+                }
+              },
+              "bazel_tools@_": {
+                "name": "bazel_tools",
+                "version": "",
+                "key": "bazel_tools@_",
+                "repoName": "bazel_tools"
+              },
+              "karrot_proto@_": {
+                "name": "karrot_proto",
+                "version": "",
+                "key": "karrot_proto@_",
+                "repoName": "karrot_proto",
+                "repoSpec": {
+                  "bzlFile": "@@bazel_tools//tools/build_defs/repo:git.bzl",
+                  "ruleClassName": "git_repository",
+                  "attributes": {
+                    "name": "karrot_proto~override",
+                    "remote": "https://github.com/daangn/karrot-proto.git",
+                    "commit": "de5d97557a09ad61ae6ac48b1258b67d304660f0"
+                  }
+                }
+              },
+              "local_config_platform@_": {
+                "name": "local_config_platform",
+                "version": "",
+                "key": "local_config_platform@_",
+                "repoName": "local_config_platform",
+                "repoSpec": {
+                  "bzlFile": "@@bazel_tools//tools/build_defs/repo:local.bzl",
+                  "ruleClassName": "local_config_platform",
+                  "attributes": {
+                    "name": "local_config_platform"
+                  }
+                }
+              },
+              "rules_foo@_": {
+                "name": "rules_foo",
+                "version": "",
+                "key": "rules_foo@_",
+                "repoName": "rules_foo",
+                "repoSpec": {
+                  "bzlFile": "@@bazel_tools//tools/build_defs/repo:http.bzl",
+                  "ruleClassName": "http_archive",
+                  "attributes": {
+                    "name": "rules_foo~override",
+                    "urls": [
+                      "https://example.com/rules_foo-1.0.tar.gz"
+                    ],
+                    "integrity": "sha256-47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU=",
+                    "strip_prefix": "rules_foo-1.0"
+                  }
+                }
+              },
+              "rules_go@0.41.0": {
+                "name": "rules_go",
+                "version": "0.41.0",
+                "key": "rules_go@0.41.0",
+                "repoName": "io_bazel_rules_go",
+                "repoSpec": {
+                  "bzlFile": "@@bazel_tools//tools/build_defs/repo:http.bzl",
+                  "ruleClassName": "http_archive",
+                  "attributes": {
+                    "name": "rules_go~0.41.0",
+                    "urls": [
+                      "https://github.com/bazelbuild/rules_go/releases/download/v0.41.0/rules_go-v0.41.0.zip"
+                    ],
+                    "integrity": "sha256-...",
+                    "strip_prefix": ""
+                  }
+                }
+              }
+            }
+          }
+        "#};
+
+        let result = normalize(lockfile).unwrap();
+        assert_eq!(
+            result,
+            HashSet::from([
+                Dependency::Bazel {
+                    name: "rules_go".into(),
+                    version: "0.41.0".into(),
+                },
+                Dependency::GitHub {
+                    owner: "daangn".into(),
+                    name: "karrot-proto".into(),
+                    head: Some("de5d97557a09ad61ae6ac48b1258b67d304660f0".into()),
+                },
+                Dependency::Url {
+                    url: "https://example.com/rules_foo-1.0.tar.gz".into(),
+                    hash: Some("sha256-47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU=".into()),
+                },
+            ]),
+        );
+    }
+
+    #[test]
+    fn test_normalize_registry_file_hashes() {
+        let lockfile = indoc! {r#"
+          {
+            "lockFileVersion": 13,
+            "registryFileHashes": {
+              "https://bcr.bazel.build/bazel_registry.json": "8a28e4aff06ee60aed2a8c281907fb8bcbf3b753c91fb5a5c57447b9ca6a1e4d",
+              "https://bcr.bazel.build/modules/abseil-cpp/20210324.2/MODULE.bazel": "7cd0312e064fde87c8d1cd79ba06c876bd23630c83466e9500321be55c96ace2",
+              "https://bcr.bazel.build/modules/abseil-cpp/20230802.0/MODULE.bazel": "d253ae36a8bd9ee3c5955384096ccb6baf16a1b1e93e858370da0a3b94f77c16",
+              "https://bcr.bazel.build/modules/abseil-cpp/20230802.0/source.json": "36a6c4b44e2e0ab6a0e2e9b2b4d7b02a4ad3e19b5f8d6e4a1b3c5d7e9f0a1b2c",
+              "https://bcr.bazel.build/modules/rules_cc/0.0.9/MODULE.bazel": "836e76439f354b89afe6a911a7adf59a6b2518fafb174483ad78a2a2fde7b1c5",
+              "https://bcr.bazel.build/modules/rules_cc/0.0.9/source.json": "1f1ba6fea244b616de4a554a0f4983c91a9301640c8fe0dd1d410254115c8430",
+              "https://registry.example.com/modules/karrot_rules/1.0.0/source.json": "..."
+            },
+            "selectedYankedVersions": {},
+            "moduleExtensions": {}
+          }
+        "#};
+
+        let result = normalize(lockfile).unwrap();
+        assert_eq!(
+            result,
+            HashSet::from([
+                Dependency::Bazel {
+                    name: "abseil-cpp".into(),
+                    version: "20230802.0".into(),
+                },
+                Dependency::Bazel {
+                    name: "rules_cc".into(),
+                    version: "0.0.9".into(),
+                },
+            ]),
+        );
+    }
+}
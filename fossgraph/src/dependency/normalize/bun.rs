@@ -0,0 +1,327 @@
+use std::collections::HashSet;
+
+use crate::dependency::Dependency;
+
+use serde_json::Value;
+
+use super::npm::{is_git_resolution, normalize_codeload_tarball, normalize_git_resolution};
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum Error {
+    #[error("Couldn't parse the lockfile.\n{message}")]
+    InvalidLockfileFormat { message: String },
+
+    #[error("Unsupported resolution: {resolution}")]
+    UnsupportedResolution { resolution: String },
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(_error: serde_json::Error) -> Self {
+        Self::invalid_json()
+    }
+}
+
+impl Error {
+    fn invalid_json() -> Self {
+        Self::InvalidLockfileFormat {
+            message: "Not a valid JSON".into(),
+        }
+    }
+
+    fn invalid_format() -> Self {
+        Self::InvalidLockfileFormat {
+            message: "Malformed lockfile".into(),
+        }
+    }
+
+    fn invalid_package(key: &str) -> Self {
+        Self::InvalidLockfileFormat {
+            message: format!("Some package has unsupported form: {key}"),
+        }
+    }
+}
+
+// Lockfile layout:
+//
+// bun.lock is JSONC (JSON with comments and trailing commas).
+//
+// `workspaces` maps the directories of workspace members (the root is `""`) to their manifests.
+// `packages` maps install paths (e.g. `a/@b/c` for nested one) to a tuple,
+// the first element of which is a resolution `<ident>@<range>`.
+//
+// - npm: `[<ident>@<version>, <registry>, <info>, <integrity>]`, empty registry is the default one
+// - git: `[<ident>@git+<url>#<commit>, <info>, <commit>]`
+// - github: `[<ident>@github:<owner>/<name>#<commit>, <info>, <owner>-<name>-<commit>]`
+// - tarball: `[<ident>@<url>, <info>]`
+// - workspace: `[<ident>@workspace:<path>]`
+// - local: `[<ident>@file:<path>, <info>]`, `[<ident>@link:<path>]`
+
+const NPM_REGISTRIES: [&str; 2] = ["", "https://registry.npmjs.org/"];
+
+/// Strips comments and trailing commas, so it can be parsed as JSON.
+fn strip_jsonc(value: &str) -> String {
+    let mut stripped = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+    // position of the last comma, until anything but whitespace and comments follows it
+    let mut last_comma: Option<usize> = None;
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                last_comma = None;
+                stripped.push(c);
+                while let Some(c) = chars.next() {
+                    stripped.push(c);
+                    match c {
+                        '\\' => stripped.extend(chars.next()),
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        stripped.push(c);
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut last = None;
+                for c in chars.by_ref() {
+                    if last == Some('*') && c == '/' {
+                        break;
+                    }
+                    last = Some(c);
+                }
+            }
+            ',' => {
+                last_comma = Some(stripped.len());
+                stripped.push(c);
+            }
+            '}' | ']' => {
+                if let Some(index) = last_comma.take() {
+                    stripped.remove(index);
+                }
+                stripped.push(c);
+            }
+            _ => {
+                if !c.is_whitespace() {
+                    last_comma = None;
+                }
+                stripped.push(c);
+            }
+        }
+    }
+    stripped
+}
+
+fn split_resolution(resolution: &str) -> Option<(&str, &str)> {
+    let index = resolution.get(1..)?.find('@')? + 1;
+    Some((&resolution[..index], &resolution[index + 1..]))
+}
+
+fn normalize_single_package(key: &str, package: &[Value]) -> Result<Dependency, Error> {
+    let (ident, range) = package
+        .first()
+        .and_then(Value::as_str)
+        .and_then(split_resolution)
+        .ok_or_else(|| Error::invalid_package(key))?;
+
+    if is_git_resolution(range) {
+        return Ok(normalize_git_resolution(range));
+    }
+    if range.starts_with("https://") || range.starts_with("http://") {
+        // arbitrary tarball is not supported
+        return normalize_codeload_tarball(range).ok_or_else(|| Error::UnsupportedResolution {
+            resolution: range.into(),
+        });
+    }
+    if ["workspace:", "file:", "link:"]
+        .iter()
+        .any(|protocol| range.starts_with(protocol))
+    {
+        // workspace members and local packages are not supported
+        return Err(Error::UnsupportedResolution {
+            resolution: range.into(),
+        });
+    }
+    let registry = package
+        .get(1)
+        .and_then(Value::as_str)
+        .ok_or_else(|| Error::invalid_package(key))?;
+    // private/custom registry is not supported
+    if !NPM_REGISTRIES.contains(&registry) {
+        return Err(Error::UnsupportedResolution {
+            resolution: registry.into(),
+        });
+    }
+    Ok(Dependency::Npm {
+        name: ident.into(),
+        version: range.into(),
+    })
+}
+
+fn normalize_json(value: Value) -> Result<HashSet<Dependency>, Error> {
+    let mut deps: HashSet<Dependency> = HashSet::new();
+
+    if !value.get("workspaces").is_some_and(Value::is_object) {
+        return Err(Error::invalid_format());
+    }
+    let Some(packages) = value.get("packages") else {
+        return Ok(deps);
+    };
+    let Some(packages) = packages.as_object() else {
+        return Err(Error::invalid_format());
+    };
+    for (key, package) in packages {
+        let Some(package) = package.as_array() else {
+            return Err(Error::invalid_format());
+        };
+        match normalize_single_package(key, package) {
+            Ok(dependency) => {
+                deps.insert(dependency);
+            }
+            Err(Error::UnsupportedResolution { .. }) => {
+                // noop
+            }
+            Err(error) => {
+                return Err(error);
+            }
+        }
+    }
+    Ok(deps)
+}
+
+pub fn normalize(value: &str) -> Result<HashSet<Dependency>, Error> {
+    let json: Value = serde_json::from_str(strip_jsonc(value).as_str())?;
+    normalize_json(json)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let lockfile = indoc! {r#"
+          {
+            "lockfileVersion": 1,
+            "workspaces": {
+              "": {
+                "name": "bun-lock",
+                "dependencies": {
+                  "@karrot/ui": "workspace:*",
+                  "cjk-slug": "github:daangn/cjk-slug",
+                  "lodash-es": "npm:lodash@^4.17.21",
+                  "private-lib": "^1.0.0",
+                  "semver": "^7.5.1",
+                },
+              },
+              "packages/ui": {
+                "name": "@karrot/ui",
+                "dependencies": {
+                  "internal-kit": "git+ssh://git@git.example.com/js/internal-kit.git",
+                  "local-utils": "file:../local-utils",
+                },
+              },
+            },
+            "packages": {
+              "@karrot/ui": ["@karrot/ui@workspace:packages/ui"],
+
+              "cjk-slug": ["cjk-slug@github:daangn/cjk-slug#de5d975", { "dependencies": { "normalize-cjk": "^0.4.0" } }, "daangn-cjk-slug-de5d975"],
+
+              "internal-kit": ["internal-kit@git+ssh://git@git.example.com/js/internal-kit.git#0123456789abcdef0123456789abcdef01234567", {}, "0123456789abcdef0123456789abcdef01234567"],
+
+              "local-utils": ["local-utils@file:../local-utils", {}],
+
+              "lodash-es": ["lodash@4.17.21", "", {}, "sha512-..."],
+
+              "lru-cache": ["lru-cache@6.0.0", "", { "dependencies": { "yallist": "^4.0.0" } }, "sha512-..."],
+
+              "normalize-cjk": ["normalize-cjk@0.4.0", "", {}, "sha512-..."],
+
+              "private-lib": ["private-lib@1.0.0", "https://npm.example.com/", {}, "sha512-..."],
+
+              // nested install
+              "semver": ["semver@7.5.1", "", { "dependencies": { "lru-cache": "^6.0.0" }, "bin": { "semver": "bin/semver.js" } }, "sha512-..."],
+
+              "semver/lru-cache": ["lru-cache@9.1.2", "", {}, "sha512-..."],
+
+              "yallist": ["yallist@4.0.0", "", {}, "sha512-..."],
+            }
+          }
+        "#};
+
+        let result = normalize(lockfile).unwrap();
+        assert_eq!(
+            result,
+            HashSet::from([
+                Dependency::GitHub {
+                    owner: "daangn".into(),
+                    name: "cjk-slug".into(),
+                    head: Some("de5d975".into()),
+                },
+                Dependency::Git {
+                    url: "git@git.example.com:js/internal-kit.git".into(),
+                    head: Some("0123456789abcdef0123456789abcdef01234567".into()),
+                },
+                Dependency::Npm {
+                    name: "lodash".into(),
+                    version: "4.17.21".into(),
+                },
+                Dependency::Npm {
+                    name: "lru-cache".into(),
+                    version: "6.0.0".into(),
+                },
+                Dependency::Npm {
+                    name: "lru-cache".into(),
+                    version: "9.1.2".into(),
+                },
+                Dependency::Npm {
+                    name: "normalize-cjk".into(),
+                    version: "0.4.0".into(),
+                },
+                Dependency::Npm {
+                    name: "semver".into(),
+                    version: "7.5.1".into(),
+                },
+                Dependency::Npm {
+                    name: "yallist".into(),
+                    version: "4.0.0".into(),
+                },
+            ]),
+        );
+    }
+    #[test]
+    fn test_strip_jsonc() {
+        let jsonc = indoc! {r#"
+          {
+            // comment
+            "packages": {
+              "a": ["a@1.0.0", "", {}, "sha512-..."], // trailing comment
+              /* block */ "b": ["b@1.0.0", "https://example.com/b.tgz", {}, "sha512-//..."],
+              // last comment
+            },
+            "workspaces": [
+              "packages/*", /* block */
+            ],
+          }
+        "#};
+
+        let value: Value = serde_json::from_str(&strip_jsonc(jsonc)).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "packages": {
+                    "a": ["a@1.0.0", "", {}, "sha512-..."],
+                    "b": ["b@1.0.0", "https://example.com/b.tgz", {}, "sha512-//..."],
+                },
+                "workspaces": ["packages/*"],
+            }),
+        );
+    }
+}
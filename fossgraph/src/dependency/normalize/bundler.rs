@@ -0,0 +1,304 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::dependency::Dependency;
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum Error {
+    #[error("Couldn't parse the lockfile.\n{message}")]
+    InvalidLockfileFormat { message: String },
+
+    #[error("Unsupported resolution: {resolution}")]
+    UnsupportedResolution { resolution: String },
+}
+
+impl Error {
+    fn invalid_line(line_number: usize) -> Self {
+        Self::InvalidLockfileFormat {
+            message: format!("Unexpected syntax at line {line_number}"),
+        }
+    }
+
+    fn missing_option(section: &str, option: &str) -> Self {
+        Self::InvalidLockfileFormat {
+            message: format!("Some {section} section has no {option}"),
+        }
+    }
+}
+
+// Lockfile syntax:
+//
+// ```
+// GIT
+//   remote: <url>
+//   revision: <commit>
+//   specs:
+//     <name> (<version>)
+//       <dependency> (<requirement>)
+//
+// GEM
+//   remote: https://rubygems.org/
+//   specs:
+//     <name> (<version>(-<platform>))
+//
+// PLATFORMS
+//   <platform>
+//
+// BUNDLED WITH
+//    <version>
+// ```
+//
+// Sections are separated by a blank line and may appear more than once
+// (e.g. a `GIT` section per repository).
+//
+// Only `GIT` and `GEM` sections carry resolutions. `PATH` gems are local,
+// and `PLATFORMS`, `DEPENDENCIES`, `RUBY VERSION` and `BUNDLED WITH` are metadata.
+
+const RUBYGEMS_REMOTES: [&str; 2] = ["https://rubygems.org/", "https://rubygems.org"];
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Section {
+    name: String,
+    options: HashMap<String, Vec<String>>,
+    specs: Vec<(String, String)>,
+}
+
+impl Section {
+    fn option(&self, key: &str) -> Option<&str> {
+        self.options
+            .get(key)
+            .and_then(|values| values.first())
+            .map(String::as_str)
+    }
+}
+
+fn parse(value: &str) -> Result<Vec<Section>, Error> {
+    let mut sections: Vec<Section> = Vec::new();
+
+    for (index, line) in value.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let indent = line.len() - trimmed.len();
+        if indent == 0 {
+            sections.push(Section {
+                name: trimmed.trim_end().into(),
+                ..Section::default()
+            });
+            continue;
+        }
+        let Some(section) = sections.last_mut() else {
+            return Err(Error::invalid_line(index + 1));
+        };
+        let trimmed = trimmed.trim_end();
+        match indent {
+            // metadata sections like `PLATFORMS` have bare values
+            2 => match trimmed.split_once(':') {
+                Some((key, value)) => {
+                    let values = section.options.entry(key.into()).or_default();
+                    if !value.is_empty() {
+                        values.push(value.trim().into());
+                    }
+                }
+                None => {
+                    section.options.entry(trimmed.into()).or_default();
+                }
+            },
+            4 => {
+                let Some((name, version)) = trimmed
+                    .split_once(" (")
+                    .and_then(|(name, version)| Some((name, version.strip_suffix(')')?)))
+                else {
+                    return Err(Error::invalid_line(index + 1));
+                };
+                section.specs.push((name.into(), version.into()));
+            }
+            _ => {
+                // noop: dependencies of specs and `BUNDLED WITH` version
+            }
+        }
+    }
+    Ok(sections)
+}
+
+fn split_platform(version: &str) -> (&str, Option<&str>) {
+    match version.split_once('-') {
+        Some((version, platform)) => (version, Some(platform)),
+        None => (version, None),
+    }
+}
+
+fn normalize_section(section: &Section) -> Result<Vec<Dependency>, Error> {
+    match section.name.as_str() {
+        "GIT" => {
+            let remote = section
+                .option("remote")
+                .ok_or_else(|| Error::missing_option("GIT", "remote"))?;
+            let revision = section
+                .option("revision")
+                .ok_or_else(|| Error::missing_option("GIT", "revision"))?;
+            Ok(vec![Dependency::Git {
+                url: remote.into(),
+                head: Some(revision.into()),
+            }
+            .canonicalize()])
+        }
+        "GEM" => {
+            let remotes = section
+                .options
+                .get("remote")
+                .ok_or_else(|| Error::missing_option("GEM", "remote"))?;
+            // private gem server is not supported
+            if !remotes
+                .iter()
+                .any(|remote| RUBYGEMS_REMOTES.contains(&remote.as_str()))
+            {
+                return Err(Error::UnsupportedResolution {
+                    resolution: remotes.join(", "),
+                });
+            }
+            Ok(section
+                .specs
+                .iter()
+                .map(|(name, version)| {
+                    let (version, platform) = split_platform(version);
+                    Dependency::RubyGem {
+                        name: name.into(),
+                        version: version.into(),
+                        platform: platform.map(String::from),
+                    }
+                })
+                .collect())
+        }
+        _ => Ok(Vec::new()),
+    }
+}
+
+pub fn normalize(value: &str) -> Result<HashSet<Dependency>, Error> {
+    let mut deps: HashSet<Dependency> = HashSet::new();
+
+    for section in parse(value)? {
+        match normalize_section(&section) {
+            Ok(dependencies) => {
+                deps.extend(dependencies);
+            }
+            Err(Error::UnsupportedResolution { .. }) => {
+                // noop
+            }
+            Err(error) => {
+                return Err(error);
+            }
+        }
+    }
+    Ok(deps)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let lockfile = indoc! {r#"
+          GIT
+            remote: https://github.com/daangn/karrot-auth.git
+            revision: de5d97557a09ad61ae6ac48b1258b67d304660f0
+            branch: main
+            specs:
+              karrot-auth (0.3.1)
+                rack (>= 2.0)
+
+          GIT
+            remote: git@git.example.com:team/internal-client.git
+            revision: 0123456789abcdef0123456789abcdef01234567
+            tag: v1.0.0
+            specs:
+              internal-client (1.0.0)
+
+          PATH
+            remote: engines/admin
+            specs:
+              admin (0.1.0)
+
+          GEM
+            remote: https://gems.example.com/
+            specs:
+              private-gem (2.0.0)
+
+          GEM
+            remote: https://rubygems.org/
+            specs:
+              nokogiri (1.15.2-arm64-darwin)
+                racc (~> 1.4)
+              nokogiri (1.15.2-x86_64-linux)
+                racc (~> 1.4)
+              racc (1.7.1)
+              rack (3.0.8)
+
+          PLATFORMS
+            arm64-darwin-22
+            x86_64-linux
+
+          DEPENDENCIES
+            admin!
+            internal-client!
+            karrot-auth!
+            nokogiri
+            private-gem!
+
+          BUNDLED WITH
+             2.4.13
+        "#};
+
+        let result = normalize(lockfile).unwrap();
+        assert_eq!(
+            result,
+            HashSet::from([
+                Dependency::RubyGem {
+                    name: "nokogiri".into(),
+                    version: "1.15.2".into(),
+                    platform: Some("arm64-darwin".into()),
+                },
+                Dependency::RubyGem {
+                    name: "nokogiri".into(),
+                    version: "1.15.2".into(),
+                    platform: Some("x86_64-linux".into()),
+                },
+                Dependency::RubyGem {
+                    name: "racc".into(),
+                    version: "1.7.1".into(),
+                    platform: None,
+                },
+                Dependency::RubyGem {
+                    name: "rack".into(),
+                    version: "3.0.8".into(),
+                    platform: None,
+                },
+                Dependency::GitHub {
+                    owner: "daangn".into(),
+                    name: "karrot-auth".into(),
+                    head: Some("de5d97557a09ad61ae6ac48b1258b67d304660f0".into()),
+                },
+                Dependency::Git {
+                    url: "git@git.example.com:team/internal-client.git".into(),
+                    head: Some("0123456789abcdef0123456789abcdef01234567".into()),
+                },
+            ]),
+        );
+    }
+
+    #[test]
+    fn test_normalize_missing_revision() {
+        let lockfile = indoc! {r#"
+          GIT
+            remote: https://github.com/daangn/karrot-auth.git
+            specs:
+              karrot-auth (0.3.1)
+        "#};
+
+        let result = normalize(lockfile);
+        assert_eq!(result, Err(Error::missing_option("GIT", "revision")));
+    }
+}
@@ -0,0 +1,225 @@
+use std::collections::HashSet;
+
+use crate::dependency::Dependency;
+
+use toml::{Table, Value};
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum Error {
+    #[error("Couldn't parse the lockfile.\n{message}")]
+    InvalidLockfileFormat { message: String },
+
+    #[error("Unsupported resolution: {resolution}")]
+    UnsupportedResolution { resolution: String },
+}
+
+impl From<toml::de::Error> for Error {
+    fn from(_error: toml::de::Error) -> Self {
+        Self::invalid_toml()
+    }
+}
+
+impl Error {
+    fn invalid_toml() -> Self {
+        Self::InvalidLockfileFormat {
+            message: "Not a valid TOML".into(),
+        }
+    }
+
+    fn invalid_format() -> Self {
+        Self::InvalidLockfileFormat {
+            message: "Malformed lockfile".into(),
+        }
+    }
+
+    fn invalid_source(source: &str) -> Self {
+        Self::InvalidLockfileFormat {
+            message: format!("Some package has unsupported source: {source}"),
+        }
+    }
+}
+
+// Source string parsing rule:
+//
+// "source" follows form of `<kind>+<url>`
+// "kind" is one of `registry`, `sparse` and `git`
+//
+// git sources follow form of `git+<url>(?<reference>)#<revision>`
+//    (e.g. `git+https://github.com/owner/name?branch=main#<commit>`)
+//
+// Path dependencies and workspace members have no source at all.
+// The layout of `[[package]]` tables is the same across lockfile v1 to v4.
+
+const CRATES_IO_SOURCES: [&str; 2] = [
+    "registry+https://github.com/rust-lang/crates.io-index",
+    "sparse+https://index.crates.io/",
+];
+
+fn normalize_single_package(name: &str, version: &str, source: &str) -> Result<Dependency, Error> {
+    if CRATES_IO_SOURCES.contains(&source) {
+        return Ok(Dependency::Cargo {
+            name: name.into(),
+            version: version.into(),
+        });
+    }
+    if let Some(substr) = source.strip_prefix("git+") {
+        let Some((url, revision)) = substr.split_once('#') else {
+            return Err(Error::invalid_source(source));
+        };
+        let (url, _reference) = url.split_once('?').unwrap_or((url, ""));
+        return Ok(Dependency::Git {
+            url: url.into(),
+            head: Some(revision.into()),
+        }
+        .canonicalize());
+    }
+    // alternative registry is not supported
+    if source.starts_with("registry+") || source.starts_with("sparse+") {
+        return Err(Error::UnsupportedResolution {
+            resolution: source.into(),
+        });
+    }
+    Err(Error::invalid_source(source))
+}
+
+fn normalize_toml(table: Table) -> Result<HashSet<Dependency>, Error> {
+    let mut deps: HashSet<Dependency> = HashSet::new();
+
+    let Some(packages) = table.get("package") else {
+        return Ok(deps);
+    };
+    let Some(packages) = packages.as_array() else {
+        return Err(Error::invalid_format());
+    };
+    for package in packages {
+        let Some(package) = package.as_table() else {
+            return Err(Error::invalid_format());
+        };
+        let name = package.get("name").and_then(Value::as_str);
+        let version = package.get("version").and_then(Value::as_str);
+        let (Some(name), Some(version)) = (name, version) else {
+            return Err(Error::invalid_format());
+        };
+        // path dependencies and workspace members
+        let Some(source) = package.get("source") else {
+            continue;
+        };
+        let source = source.as_str().ok_or_else(Error::invalid_format)?;
+        match normalize_single_package(name, version, source) {
+            Ok(dependency) => {
+                deps.insert(dependency);
+            }
+            Err(Error::UnsupportedResolution { .. }) => {
+                // noop
+            }
+            Err(error) => {
+                return Err(error);
+            }
+        }
+    }
+    Ok(deps)
+}
+
+pub fn normalize(value: &str) -> Result<HashSet<Dependency>, Error> {
+    let table: Table = toml::from_str(value)?;
+    normalize_toml(table)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let lockfile = indoc! {r#"
+          # This file is automatically @generated by Cargo.
+          # It is not intended for manual editing.
+          version = 3
+
+          [[package]]
+          name = "fossgraph-core"
+          version = "0.0.0"
+          dependencies = [
+           "internal-utils",
+           "serde",
+           "toml",
+          ]
+
+          [[package]]
+          name = "internal-utils"
+          version = "0.1.0"
+          source = "git+https://git.example.com/team/internal-utils.git?tag=v0.1.0#0123456789abcdef0123456789abcdef01234567"
+
+          [[package]]
+          name = "private-crate"
+          version = "1.0.0"
+          source = "sparse+https://crates.example.com/index/"
+          checksum = "..."
+
+          [[package]]
+          name = "serde"
+          version = "1.0.164"
+          source = "registry+https://github.com/rust-lang/crates.io-index"
+          checksum = "9e8c8cf938e98f769bc164923b06dce91cea1751522f46f8466461af04c9027d"
+
+          [[package]]
+          name = "toml"
+          version = "0.7.4"
+          source = "git+https://github.com/toml-rs/toml?branch=main#de5d97557a09ad61ae6ac48b1258b67d304660f0"
+
+          [[package]]
+          name = "unicode-ident"
+          version = "1.0.9"
+          source = "sparse+https://index.crates.io/"
+          checksum = "b15811caf2415fb889178633e7724bad2509101cde276048e013b9def5e51fa0"
+        "#};
+
+        let result = normalize(lockfile).unwrap();
+        assert_eq!(
+            result,
+            HashSet::from([
+                Dependency::Cargo {
+                    name: "serde".into(),
+                    version: "1.0.164".into(),
+                },
+                Dependency::Cargo {
+                    name: "unicode-ident".into(),
+                    version: "1.0.9".into(),
+                },
+                Dependency::GitHub {
+                    owner: "toml-rs".into(),
+                    name: "toml".into(),
+                    head: Some("de5d97557a09ad61ae6ac48b1258b67d304660f0".into()),
+                },
+                Dependency::Git {
+                    url: "https://git.example.com/team/internal-utils.git".into(),
+                    head: Some("0123456789abcdef0123456789abcdef01234567".into()),
+                },
+            ]),
+        );
+    }
+
+    #[test]
+    fn test_normalize_v1() {
+        let lockfile = indoc! {r#"
+          [[package]]
+          name = "serde"
+          version = "1.0.164"
+          source = "registry+https://github.com/rust-lang/crates.io-index"
+
+          [metadata]
+          "checksum serde 1.0.164 (registry+https://github.com/rust-lang/crates.io-index)" = "9e8c8cf938e98f769bc164923b06dce91cea1751522f46f8466461af04c9027d"
+        "#};
+
+        let result = normalize(lockfile).unwrap();
+        assert_eq!(
+            result,
+            HashSet::from([Dependency::Cargo {
+                name: "serde".into(),
+                version: "1.0.164".into(),
+            }]),
+        );
+    }
+}
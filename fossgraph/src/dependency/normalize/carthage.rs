@@ -0,0 +1,145 @@
+use std::collections::HashSet;
+
+use crate::dependency::Dependency;
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum Error {
+    #[error("Couldn't parse the lockfile.\n{message}")]
+    InvalidLockfileFormat { message: String },
+}
+
+impl Error {
+    fn invalid_line(line_number: usize) -> Self {
+        Self::InvalidLockfileFormat {
+            message: format!("Unexpected syntax at line {line_number}"),
+        }
+    }
+}
+
+// Cartfile.resolved syntax:
+//
+// ```
+// github "<owner>/<name>" "<tag or commit>"
+// github "<GitHub Enterprise URL>" "<tag or commit>"
+// git "<url>" "<tag or commit>"
+// binary "<URL of the binary project specification>" "<version>"
+// ```
+//
+// `binary` projects are prebuilt frameworks, which are usually closed-source.
+
+/// Splits a line into the keyword and the quoted strings.
+fn split_line(line: &str) -> Option<(&str, Vec<&str>)> {
+    let (keyword, mut rest) = line.split_once(' ')?;
+    let mut values: Vec<&str> = Vec::new();
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() || rest.starts_with('#') {
+            return Some((keyword, values));
+        }
+        let (value, substr) = rest.strip_prefix('"')?.split_once('"')?;
+        values.push(value);
+        rest = substr;
+    }
+}
+
+fn normalize_line(line: &str) -> Option<Dependency> {
+    let (keyword, values) = split_line(line)?;
+    let [location, head] = values.as_slice() else {
+        return None;
+    };
+    match keyword {
+        "github" if !location.contains("://") => {
+            let (owner, name) = location.split_once('/')?;
+            Some(Dependency::GitHub {
+                owner: owner.into(),
+                name: name.into(),
+                head: Some((*head).into()),
+            })
+        }
+        "github" | "git" => Some(
+            Dependency::Git {
+                url: (*location).into(),
+                head: Some((*head).into()),
+            }
+            .canonicalize(),
+        ),
+        "binary" => Some(Dependency::Binary {
+            url: (*location).into(),
+            version: (*head).into(),
+        }),
+        _ => None,
+    }
+}
+
+pub fn normalize(value: &str) -> Result<HashSet<Dependency>, Error> {
+    let mut deps: HashSet<Dependency> = HashSet::new();
+
+    for (index, line) in value.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some(dependency) = normalize_line(line) else {
+            return Err(Error::invalid_line(index + 1));
+        };
+        deps.insert(dependency);
+    }
+    Ok(deps)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let lockfile = indoc! {r#"
+          binary "https://dl.google.com/dl/firebase/ios/carthage/FirebaseAnalyticsBinary.json" "10.12.0"
+          git "https://git.example.com/ios/KarrotKit.git" "0123456789abcdef0123456789abcdef01234567"
+          github "Alamofire/Alamofire" "5.7.1"
+          github "ReactiveX/RxSwift" "6.5.0"
+          github "https://ghe.example.com/ios/Analytics" "v2.1.0"
+        "#};
+
+        let result = normalize(lockfile).unwrap();
+        assert_eq!(
+            result,
+            HashSet::from([
+                Dependency::Binary {
+                    url: "https://dl.google.com/dl/firebase/ios/carthage/FirebaseAnalyticsBinary.json".into(),
+                    version: "10.12.0".into(),
+                },
+                Dependency::Git {
+                    url: "https://git.example.com/ios/KarrotKit.git".into(),
+                    head: Some("0123456789abcdef0123456789abcdef01234567".into()),
+                },
+                Dependency::GitHub {
+                    owner: "Alamofire".into(),
+                    name: "Alamofire".into(),
+                    head: Some("5.7.1".into()),
+                },
+                Dependency::GitHub {
+                    owner: "ReactiveX".into(),
+                    name: "RxSwift".into(),
+                    head: Some("6.5.0".into()),
+                },
+                Dependency::Git {
+                    url: "https://ghe.example.com/ios/Analytics".into(),
+                    head: Some("v2.1.0".into()),
+                },
+            ]),
+        );
+    }
+
+    #[test]
+    fn test_normalize_invalid() {
+        let lockfile = indoc! {r#"
+          github "Alamofire/Alamofire" "5.7.1"
+          github "ReactiveX/RxSwift"
+        "#};
+
+        assert_eq!(normalize(lockfile), Err(Error::invalid_line(2)));
+    }
+}
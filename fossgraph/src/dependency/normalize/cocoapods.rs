@@ -1 +1,402 @@
+use std::collections::{HashMap, HashSet};
 
+use crate::dependency::Dependency;
+
+use serde_yaml::{Mapping, Value};
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum Error {
+    #[error("Couldn't parse the lockfile.\n{message}")]
+    InvalidLockfileFormat { message: String },
+
+    #[error("Unsupported resolution: {resolution}")]
+    UnsupportedResolution { resolution: String },
+}
+
+impl From<serde_yaml::Error> for Error {
+    fn from(_error: serde_yaml::Error) -> Self {
+        Self::invalid_yaml()
+    }
+}
+
+impl Error {
+    fn invalid_yaml() -> Self {
+        Self::InvalidLockfileFormat {
+            message: "Not a valid YAML".into(),
+        }
+    }
+
+    fn invalid_format() -> Self {
+        Self::InvalidLockfileFormat {
+            message: "Malformed lockfile".into(),
+        }
+    }
+
+    fn invalid_pod(pod: &str) -> Self {
+        Self::InvalidLockfileFormat {
+            message: format!("Some pod has unsupported form: {pod}"),
+        }
+    }
+
+    fn unresolved_dependency(dependency: &str) -> Self {
+        Self::InvalidLockfileFormat {
+            message: format!("Some dependency is not resolved: {dependency}"),
+        }
+    }
+}
+
+// Pod string parsing rule:
+//
+// "pod" follows form of `<name>( (<requirement>))`
+// "name" is `<root>(/<subspec>)` (e.g. Firebase, Firebase/Core)
+//
+// In `PODS` the requirement is the resolved version.
+// In `DEPENDENCIES` it is a version requirement or an external source
+// (e.g. `(~> 5.6)`, ``(from `https://github.com/owner/name.git`, commit `abc`)``).
+//
+// Subspecs are always resolved to the same version as their root spec,
+// so they are collapsed onto the root.
+
+// CocoaPods 1.7 and 1.8 write the master repo in lowercase (`https://github.com/cocoapods/specs.git`).
+const TRUNK_REPOS: [&str; 2] = ["trunk", "https://github.com/CocoaPods/Specs.git"];
+
+fn split_pod(pod: &str) -> (&str, Option<&str>) {
+    match pod.split_once(" (") {
+        Some((name, requirement)) => (name, requirement.strip_suffix(')')),
+        None => (pod, None),
+    }
+}
+
+fn root_name(name: &str) -> &str {
+    name.split('/').next().unwrap()
+}
+
+fn get_section<'a>(map: &'a Mapping, key: &str) -> Option<&'a Value> {
+    map.get(key).filter(|value| !value.is_null())
+}
+
+/// `PODS` entries are either `<pod>` or a mapping of `<pod>` to its dependencies.
+fn parse_pods(value: &Value) -> Result<HashMap<String, String>, Error> {
+    let Some(list) = value.as_sequence() else {
+        return Err(Error::invalid_format());
+    };
+    let mut pods: HashMap<String, String> = HashMap::new();
+    for item in list {
+        let pod = match item {
+            Value::String(pod) => pod.as_str(),
+            Value::Mapping(map) => map
+                .keys()
+                .next()
+                .and_then(Value::as_str)
+                .ok_or_else(Error::invalid_format)?,
+            _ => return Err(Error::invalid_format()),
+        };
+        let (name, Some(version)) = split_pod(pod) else {
+            return Err(Error::invalid_pod(pod));
+        };
+        pods.insert(root_name(name).into(), version.into());
+    }
+    Ok(pods)
+}
+
+fn parse_dependencies(value: &Value) -> Result<Vec<String>, Error> {
+    let Some(list) = value.as_sequence() else {
+        return Err(Error::invalid_format());
+    };
+    list.iter()
+        .map(|item| {
+            let pod = item.as_str().ok_or_else(Error::invalid_format)?;
+            let (name, _requirement) = split_pod(pod);
+            Ok(root_name(name).into())
+        })
+        .collect()
+}
+
+/// Maps each pod to the spec repo it was resolved from.
+fn parse_spec_repos(value: &Value) -> Result<HashMap<String, String>, Error> {
+    let Some(map) = value.as_mapping() else {
+        return Err(Error::invalid_format());
+    };
+    let mut repos: HashMap<String, String> = HashMap::new();
+    for (repo, pods) in map {
+        let (Some(repo), Some(pods)) = (repo.as_str(), pods.as_sequence()) else {
+            return Err(Error::invalid_format());
+        };
+        for pod in pods {
+            let pod = pod.as_str().ok_or_else(Error::invalid_format)?;
+            repos.insert(pod.into(), repo.into());
+        }
+    }
+    Ok(repos)
+}
+
+/// Maps each pod to its source options (e.g. `:git`, `:commit`, `:path`).
+fn parse_sources(value: &Value) -> Result<HashMap<String, HashMap<String, String>>, Error> {
+    let Some(map) = value.as_mapping() else {
+        return Err(Error::invalid_format());
+    };
+    let mut sources: HashMap<String, HashMap<String, String>> = HashMap::new();
+    for (pod, options) in map {
+        let (Some(pod), Some(options)) = (pod.as_str(), options.as_mapping()) else {
+            return Err(Error::invalid_format());
+        };
+        let options = options
+            .iter()
+            .filter_map(|(key, value)| Some((key.as_str()?.into(), value.as_str()?.into())))
+            .collect();
+        sources.insert(pod.into(), options);
+    }
+    Ok(sources)
+}
+
+fn normalize_external_source(
+    name: &str,
+    source: &HashMap<String, String>,
+) -> Result<Dependency, Error> {
+    // local pods and standalone podspecs are not supported
+    let Some(url) = source.get(":git") else {
+        return Err(Error::UnsupportedResolution {
+            resolution: name.into(),
+        });
+    };
+    let head = [":commit", ":tag", ":branch"]
+        .iter()
+        .find_map(|key| source.get(*key))
+        .cloned();
+    Ok(Dependency::Git {
+        url: url.into(),
+        head,
+    }
+    .canonicalize())
+}
+
+fn normalize_yaml(value: Value) -> Result<HashSet<Dependency>, Error> {
+    let Some(map) = value.as_mapping() else {
+        return Err(Error::invalid_format());
+    };
+
+    let pods = get_section(map, "PODS")
+        .map(parse_pods)
+        .transpose()?
+        .unwrap_or_default();
+    let dependencies = get_section(map, "DEPENDENCIES")
+        .map(parse_dependencies)
+        .transpose()?
+        .unwrap_or_default();
+    let spec_repos = get_section(map, "SPEC REPOS")
+        .map(parse_spec_repos)
+        .transpose()?
+        .unwrap_or_default();
+    let external_sources = get_section(map, "EXTERNAL SOURCES")
+        .map(parse_sources)
+        .transpose()?
+        .unwrap_or_default();
+    let checkout_options = get_section(map, "CHECKOUT OPTIONS")
+        .map(parse_sources)
+        .transpose()?
+        .unwrap_or_default();
+
+    if let Some(dependency) = dependencies.iter().find(|name| !pods.contains_key(*name)) {
+        return Err(Error::unresolved_dependency(dependency));
+    }
+
+    let mut deps: HashSet<Dependency> = HashSet::new();
+    for (name, version) in pods {
+        // checkout options have the pinned revision of the external source
+        let source = checkout_options
+            .get(&name)
+            .or_else(|| external_sources.get(&name));
+        let result = match (source, spec_repos.get(&name)) {
+            (Some(source), _) => normalize_external_source(&name, source),
+            // private spec repo is not supported
+            (None, Some(repo))
+                if !TRUNK_REPOS
+                    .iter()
+                    .any(|trunk| trunk.eq_ignore_ascii_case(repo)) =>
+            {
+                Err(Error::UnsupportedResolution {
+                    resolution: format!("{name} ({repo})"),
+                })
+            }
+            (None, _) => Ok(Dependency::CocoaPods { name, version }),
+        };
+        match result {
+            Ok(dependency) => {
+                deps.insert(dependency);
+            }
+            Err(Error::UnsupportedResolution { .. }) => {
+                // noop
+            }
+            Err(error) => {
+                return Err(error);
+            }
+        }
+    }
+    Ok(deps)
+}
+
+pub fn normalize(value: &str) -> Result<HashSet<Dependency>, Error> {
+    let yaml: Value = serde_yaml::from_str(value)?;
+    normalize_yaml(yaml)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let lockfile = indoc! {r#"
+          PODS:
+            - Alamofire (5.6.4)
+            - Firebase/Core (10.9.0):
+              - Firebase/CoreOnly
+              - FirebaseAnalytics (~> 10.9.0)
+            - Firebase/CoreOnly (10.9.0):
+              - FirebaseCore (= 10.9.0)
+            - FirebaseAnalytics (10.9.0):
+              - FirebaseCore (~> 10.0)
+            - FirebaseCore (10.9.0)
+            - KarrotFlex (1.0.0)
+            - LocalPod (0.1.0)
+            - PrivatePod (2.0.0)
+            - SnapKit (5.6.0)
+
+          DEPENDENCIES:
+            - Alamofire (~> 5.6)
+            - Firebase/Core
+            - "KarrotFlex (from `https://github.com/daangn/KarrotFlex.git`, commit `de5d97557a09ad61ae6ac48b1258b67d304660f0`)"
+            - LocalPod (from `../LocalPod`)
+            - PrivatePod
+            - "SnapKit (from `git@git.example.com:ios/SnapKit.git`, tag `5.6.0`)"
+
+          SPEC REPOS:
+            "git@git.example.com:ios/private-specs.git":
+              - PrivatePod
+            trunk:
+              - Alamofire
+              - Firebase
+              - FirebaseAnalytics
+              - FirebaseCore
+
+          EXTERNAL SOURCES:
+            KarrotFlex:
+              :commit: de5d97557a09ad61ae6ac48b1258b67d304660f0
+              :git: https://github.com/daangn/KarrotFlex.git
+            LocalPod:
+              :path: "../LocalPod"
+            SnapKit:
+              :git: "git@git.example.com:ios/SnapKit.git"
+              :tag: 5.6.0
+
+          CHECKOUT OPTIONS:
+            KarrotFlex:
+              :commit: de5d97557a09ad61ae6ac48b1258b67d304660f0
+              :git: https://github.com/daangn/KarrotFlex.git
+            SnapKit:
+              :git: "git@git.example.com:ios/SnapKit.git"
+              :tag: 5.6.0
+
+          SPEC CHECKSUMS:
+            Alamofire: 4e95d97098eacb88856099c4fc79b526a299e48c
+
+          PODFILE CHECKSUM: 9c3ba4f8e3b1a2d5c1b0ea35c3a7b5a5e3e1f0c2
+
+          COCOAPODS: 1.12.1
+        "#};
+
+        let result = normalize(lockfile).unwrap();
+        assert_eq!(
+            result,
+            HashSet::from([
+                Dependency::CocoaPods {
+                    name: "Alamofire".into(),
+                    version: "5.6.4".into(),
+                },
+                Dependency::CocoaPods {
+                    name: "Firebase".into(),
+                    version: "10.9.0".into(),
+                },
+                Dependency::CocoaPods {
+                    name: "FirebaseAnalytics".into(),
+                    version: "10.9.0".into(),
+                },
+                Dependency::CocoaPods {
+                    name: "FirebaseCore".into(),
+                    version: "10.9.0".into(),
+                },
+                Dependency::GitHub {
+                    owner: "daangn".into(),
+                    name: "KarrotFlex".into(),
+                    head: Some("de5d97557a09ad61ae6ac48b1258b67d304660f0".into()),
+                },
+                Dependency::Git {
+                    url: "git@git.example.com:ios/SnapKit.git".into(),
+                    head: Some("5.6.0".into()),
+                },
+            ]),
+        );
+    }
+
+    #[test]
+    fn test_normalize_master_repo() {
+        let lockfile = indoc! {r#"
+          PODS:
+            - Alamofire (5.0.0)
+            - PrivatePod (2.0.0)
+
+          DEPENDENCIES:
+            - Alamofire (~> 5.0)
+            - PrivatePod
+
+          SPEC REPOS:
+            https://github.com/cocoapods/specs.git:
+              - Alamofire
+            https://git.example.com/ios/private-specs.git:
+              - PrivatePod
+
+          COCOAPODS: 1.8.4
+        "#};
+
+        let result = normalize(lockfile).unwrap();
+        assert_eq!(
+            result,
+            HashSet::from([Dependency::CocoaPods {
+                name: "Alamofire".into(),
+                version: "5.0.0".into(),
+            }]),
+        );
+    }
+
+    #[test]
+    fn test_normalize_unresolved_dependency() {
+        let lockfile = indoc! {r#"
+          PODS:
+            - Alamofire (5.6.4)
+
+          DEPENDENCIES:
+            - Alamofire (~> 5.6)
+            - SnapKit (~> 5.6)
+
+          COCOAPODS: 1.12.1
+        "#};
+
+        let result = normalize(lockfile);
+        assert_eq!(result, Err(Error::unresolved_dependency("SnapKit")));
+    }
+
+    #[test]
+    fn test_split_pod() {
+        assert_eq!(
+            split_pod("Firebase/Core (10.9.0)"),
+            ("Firebase/Core", Some("10.9.0"))
+        );
+        assert_eq!(split_pod("Firebase/CoreOnly"), ("Firebase/CoreOnly", None));
+        assert_eq!(
+            split_pod("LocalPod (from `../LocalPod`)"),
+            ("LocalPod", Some("from `../LocalPod`")),
+        );
+    }
+}
@@ -0,0 +1,241 @@
+use std::collections::HashSet;
+
+use crate::dependency::Dependency;
+
+use serde_json::{Map, Value};
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum Error {
+    #[error("Couldn't parse the lockfile.\n{message}")]
+    InvalidLockfileFormat { message: String },
+
+    #[error("Unsupported resolution: {resolution}")]
+    UnsupportedResolution { resolution: String },
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(_error: serde_json::Error) -> Self {
+        Self::invalid_json()
+    }
+}
+
+impl Error {
+    fn invalid_json() -> Self {
+        Self::InvalidLockfileFormat {
+            message: "Not a valid JSON".into(),
+        }
+    }
+
+    fn invalid_format() -> Self {
+        Self::InvalidLockfileFormat {
+            message: "Malformed lockfile".into(),
+        }
+    }
+}
+
+// Lockfile layout:
+//
+// `packages` and `packages-dev` are arrays of `{ name, version, source, dist, ... }`.
+//
+// Packages from Packagist have `notification-url` pointing to it.
+// Others come from the repositories of composer.json (e.g. `vcs`, `path`, private Composer repository).
+//
+// Dev versions (`dev-<branch>`, `<version>-dev`) are not releases but a mirror of a branch,
+// so the commit pinned by `source.reference` is the actual resolution.
+
+const PACKAGIST_NOTIFICATION_URL: &str = "https://packagist.org/downloads/";
+
+fn is_dev_version(version: &str) -> bool {
+    version.starts_with("dev-") || version.ends_with("-dev")
+}
+
+fn normalize_git_source(package: &Map<String, Value>) -> Option<Dependency> {
+    let source = package.get("source")?;
+    if source.get("type").and_then(Value::as_str) != Some("git") {
+        return None;
+    }
+    let url = source.get("url").and_then(Value::as_str)?;
+    let reference = source.get("reference").and_then(Value::as_str);
+    Some(
+        Dependency::Git {
+            url: url.into(),
+            head: reference.map(String::from),
+        }
+        .canonicalize(),
+    )
+}
+
+fn normalize_single_package(package: &Map<String, Value>) -> Result<Vec<Dependency>, Error> {
+    let name = package.get("name").and_then(Value::as_str);
+    let version = package.get("version").and_then(Value::as_str);
+    let (Some(name), Some(version)) = (name, version) else {
+        return Err(Error::invalid_format());
+    };
+    let git_source = normalize_git_source(package);
+
+    if package.get("notification-url").and_then(Value::as_str) == Some(PACKAGIST_NOTIFICATION_URL) {
+        let mut deps = vec![Dependency::Packagist {
+            name: name.into(),
+            version: version.into(),
+        }];
+        if is_dev_version(version) {
+            deps.extend(git_source);
+        }
+        return Ok(deps);
+    }
+    // path and private Composer repository are not supported
+    match git_source {
+        Some(dependency) => Ok(vec![dependency]),
+        None => Err(Error::UnsupportedResolution {
+            resolution: name.into(),
+        }),
+    }
+}
+
+fn normalize_json(value: Value) -> Result<HashSet<Dependency>, Error> {
+    let mut deps: HashSet<Dependency> = HashSet::new();
+
+    for section in ["packages", "packages-dev"] {
+        let Some(packages) = value.get(section) else {
+            continue;
+        };
+        let Some(packages) = packages.as_array() else {
+            return Err(Error::invalid_format());
+        };
+        for package in packages {
+            let Some(package) = package.as_object() else {
+                return Err(Error::invalid_format());
+            };
+            match normalize_single_package(package) {
+                Ok(dependencies) => {
+                    deps.extend(dependencies);
+                }
+                Err(Error::UnsupportedResolution { .. }) => {
+                    // noop
+                }
+                Err(error) => {
+                    return Err(error);
+                }
+            }
+        }
+    }
+    Ok(deps)
+}
+
+pub fn normalize(value: &str) -> Result<HashSet<Dependency>, Error> {
+    let json: Value = serde_json::from_str(value)?;
+    normalize_json(json)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let lockfile = indoc! {r#"
+          {
+            "_readme": [
+              "This file locks the dependencies of your project to a known state"
+            ],
+            "content-hash": "...",
+            "packages": [
+              {
+                "name": "daangn/admin-kit",
+                "version": "dev-main",
+                "source": {
+                  "type": "git",
+                  "url": "git@git.example.com:php/admin-kit.git",
+                  "reference": "0123456789abcdef0123456789abcdef01234567"
+                },
+                "type": "library"
+              },
+              {
+                "name": "daangn/local-utils",
+                "version": "1.0.0",
+                "dist": {
+                  "type": "path",
+                  "url": "../local-utils",
+                  "reference": "..."
+                },
+                "type": "library"
+              },
+              {
+                "name": "monolog/monolog",
+                "version": "3.4.0",
+                "source": {
+                  "type": "git",
+                  "url": "https://github.com/Seldaek/monolog.git",
+                  "reference": "e2392369686d420ca32df3803de28b5d6f76867d"
+                },
+                "dist": {
+                  "type": "zip",
+                  "url": "https://api.github.com/repos/Seldaek/monolog/zipball/e2392369686d420ca32df3803de28b5d6f76867d",
+                  "reference": "e2392369686d420ca32df3803de28b5d6f76867d",
+                  "shasum": ""
+                },
+                "type": "library",
+                "notification-url": "https://packagist.org/downloads/"
+              },
+              {
+                "name": "psr/log",
+                "version": "dev-master",
+                "source": {
+                  "type": "git",
+                  "url": "https://github.com/php-fig/log.git",
+                  "reference": "fe5ea303b0887d5caefd3d431c3e61ad47037001"
+                },
+                "type": "library",
+                "notification-url": "https://packagist.org/downloads/"
+              }
+            ],
+            "packages-dev": [
+              {
+                "name": "phpunit/phpunit",
+                "version": "10.2.6",
+                "source": {
+                  "type": "git",
+                  "url": "https://github.com/sebastianbergmann/phpunit.git",
+                  "reference": "1c17815c129f133f3019cc18e8d0c8622e6d9bcd"
+                },
+                "type": "library",
+                "notification-url": "https://packagist.org/downloads/"
+              }
+            ],
+            "aliases": [],
+            "minimum-stability": "stable",
+            "plugin-api-version": "2.3.0"
+          }
+        "#};
+
+        let result = normalize(lockfile).unwrap();
+        assert_eq!(
+            result,
+            HashSet::from([
+                Dependency::Packagist {
+                    name: "monolog/monolog".into(),
+                    version: "3.4.0".into(),
+                },
+                Dependency::Packagist {
+                    name: "psr/log".into(),
+                    version: "dev-master".into(),
+                },
+                Dependency::Packagist {
+                    name: "phpunit/phpunit".into(),
+                    version: "10.2.6".into(),
+                },
+                Dependency::GitHub {
+                    owner: "php-fig".into(),
+                    name: "log".into(),
+                    head: Some("fe5ea303b0887d5caefd3d431c3e61ad47037001".into()),
+                },
+                Dependency::Git {
+                    url: "git@git.example.com:php/admin-kit.git".into(),
+                    head: Some("0123456789abcdef0123456789abcdef01234567".into()),
+                },
+            ]),
+        );
+    }
+}
@@ -0,0 +1,325 @@
+use std::collections::HashSet;
+
+use crate::dependency::Dependency;
+
+use serde_json::{Map, Value};
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum Error {
+    #[error("Couldn't parse the lockfile.\n{message}")]
+    InvalidLockfileFormat { message: String },
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(_error: serde_json::Error) -> Self {
+        Self::invalid_json()
+    }
+}
+
+impl Error {
+    fn invalid_json() -> Self {
+        Self::InvalidLockfileFormat {
+            message: "Not a valid JSON".into(),
+        }
+    }
+
+    fn invalid_format() -> Self {
+        Self::InvalidLockfileFormat {
+            message: "Malformed lockfile".into(),
+        }
+    }
+
+    fn invalid_specifier(specifier: &str) -> Self {
+        Self::InvalidLockfileFormat {
+            message: format!("Some package has unsupported specifier: {specifier}"),
+        }
+    }
+}
+
+// Lockfile layout:
+//
+// version 3 has `specifiers`, `jsr` and `npm` under `packages`.
+// version 4 has them at the top level.
+//
+// - `specifiers` maps specifiers as written (e.g. `npm:chalk@5`) to resolutions,
+//   which are `npm:<name>@<version>` in version 3, and bare `<version>` in version 4.
+// - `jsr` is keyed by `@<scope>/<name>@<version>`.
+// - `npm` is keyed by `<name>@<version>`, followed by peer dependencies
+//   (e.g. `preact-render-to-string@6.2.1_preact@10.19.3`).
+// - `remote` maps remote module URLs to sha256 hash of their contents.
+
+/// Splits `<name>@<version>`, where name can be scoped.
+fn split_version(package: &str) -> Option<(&str, &str)> {
+    let index = package.get(1..)?.find('@')? + 1;
+    Some((&package[..index], &package[index + 1..]))
+}
+
+fn normalize_npm_package(package: &str) -> Result<Dependency, Error> {
+    let Some((name, version)) = split_version(package) else {
+        return Err(Error::invalid_specifier(package));
+    };
+    // strip peer dependencies
+    let (version, _peers) = version.split_once('_').unwrap_or((version, ""));
+    Ok(Dependency::Npm {
+        name: name.into(),
+        version: version.into(),
+    })
+}
+
+fn normalize_jsr_package(package: &str) -> Result<Dependency, Error> {
+    let Some((scope, name, version)) = split_version(package).and_then(|(ident, version)| {
+        let (scope, name) = ident.strip_prefix('@')?.split_once('/')?;
+        Some((scope, name, version))
+    }) else {
+        return Err(Error::invalid_specifier(package));
+    };
+    Ok(Dependency::Jsr {
+        scope: scope.into(),
+        name: name.into(),
+        version: version.into(),
+    })
+}
+
+fn normalize_specifier(specifier: &str, resolution: &str) -> Result<Option<Dependency>, Error> {
+    let Some((protocol, package)) = specifier.split_once(':') else {
+        return Err(Error::invalid_specifier(specifier));
+    };
+    // version 4 has only the version
+    let package = match resolution.split_once(':') {
+        Some((_, package)) => package.to_owned(),
+        None => match split_version(package) {
+            Some((name, _range)) => format!("{name}@{resolution}"),
+            None => format!("{package}@{resolution}"),
+        },
+    };
+    match protocol {
+        "npm" => normalize_npm_package(&package).map(Some),
+        "jsr" => normalize_jsr_package(&package).map(Some),
+        _ => Ok(None),
+    }
+}
+
+fn normalize_packages(packages: &Map<String, Value>) -> Result<HashSet<Dependency>, Error> {
+    let mut deps: HashSet<Dependency> = HashSet::new();
+
+    if let Some(specifiers) = packages.get("specifiers") {
+        let Some(specifiers) = specifiers.as_object() else {
+            return Err(Error::invalid_format());
+        };
+        for (specifier, resolution) in specifiers {
+            let Some(resolution) = resolution.as_str() else {
+                return Err(Error::invalid_format());
+            };
+            deps.extend(normalize_specifier(specifier, resolution)?);
+        }
+    }
+    if let Some(npm) = packages.get("npm") {
+        let Some(npm) = npm.as_object() else {
+            return Err(Error::invalid_format());
+        };
+        for package in npm.keys() {
+            deps.insert(normalize_npm_package(package)?);
+        }
+    }
+    if let Some(jsr) = packages.get("jsr") {
+        let Some(jsr) = jsr.as_object() else {
+            return Err(Error::invalid_format());
+        };
+        for package in jsr.keys() {
+            deps.insert(normalize_jsr_package(package)?);
+        }
+    }
+    Ok(deps)
+}
+
+fn normalize_json(value: Value) -> Result<HashSet<Dependency>, Error> {
+    let Some(lockfile) = value.as_object() else {
+        return Err(Error::invalid_format());
+    };
+    let packages = match lockfile.get("version").and_then(Value::as_str) {
+        Some("3") => match lockfile.get("packages") {
+            Some(packages) => packages.as_object().ok_or_else(Error::invalid_format)?,
+            None => &Map::new(),
+        },
+        Some("4") => lockfile,
+        _ => return Err(Error::invalid_format()),
+    };
+    let mut deps = normalize_packages(packages)?;
+
+    if let Some(remote) = lockfile.get("remote") {
+        let Some(remote) = remote.as_object() else {
+            return Err(Error::invalid_format());
+        };
+        for (url, hash) in remote {
+            let Some(hash) = hash.as_str() else {
+                return Err(Error::invalid_format());
+            };
+            deps.insert(Dependency::Url {
+                url: url.into(),
+                hash: Some(hash.into()),
+            });
+        }
+    }
+    Ok(deps)
+}
+
+pub fn normalize(value: &str) -> Result<HashSet<Dependency>, Error> {
+    let json: Value = serde_json::from_str(value)?;
+    normalize_json(json)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn test_normalize_v3() {
+        let lockfile = indoc! {r#"
+          {
+            "version": "3",
+            "packages": {
+              "specifiers": {
+                "jsr:@std/path@^0.221.0": "jsr:@std/path@0.221.0",
+                "npm:chalk@5": "npm:chalk@5.3.0",
+                "npm:preact-render-to-string@6.2.1": "npm:preact-render-to-string@6.2.1_preact@10.19.3"
+              },
+              "jsr": {
+                "@std/assert@0.221.0": {
+                  "integrity": "..."
+                },
+                "@std/path@0.221.0": {
+                  "integrity": "...",
+                  "dependencies": ["jsr:@std/assert@^0.221.0"]
+                }
+              },
+              "npm": {
+                "chalk@5.3.0": {
+                  "integrity": "sha512-...",
+                  "dependencies": {}
+                },
+                "preact-render-to-string@6.2.1_preact@10.19.3": {
+                  "integrity": "sha512-...",
+                  "dependencies": {
+                    "preact": "preact@10.19.3"
+                  }
+                },
+                "preact@10.19.3": {
+                  "integrity": "sha512-...",
+                  "dependencies": {}
+                }
+              }
+            },
+            "remote": {
+              "https://deno.land/x/oak@v12.6.1/mod.ts": "c4e1b1c2d3a4b5f6e7d8c9b0a1f2e3d4c5b6a7f8e9d0c1b2a3f4e5d6c7b8a9f0",
+              "https://esm.sh/lodash-es@4.17.21": "0a1b2c3d4e5f60718293a4b5c6d7e8f90a1b2c3d4e5f60718293a4b5c6d7e8f9"
+            }
+          }
+        "#};
+
+        let result = normalize(lockfile).unwrap();
+        assert_eq!(
+            result,
+            HashSet::from([
+                Dependency::Jsr {
+                    scope: "std".into(),
+                    name: "assert".into(),
+                    version: "0.221.0".into(),
+                },
+                Dependency::Jsr {
+                    scope: "std".into(),
+                    name: "path".into(),
+                    version: "0.221.0".into(),
+                },
+                Dependency::Npm {
+                    name: "chalk".into(),
+                    version: "5.3.0".into(),
+                },
+                Dependency::Npm {
+                    name: "preact-render-to-string".into(),
+                    version: "6.2.1".into(),
+                },
+                Dependency::Npm {
+                    name: "preact".into(),
+                    version: "10.19.3".into(),
+                },
+                Dependency::Url {
+                    url: "https://deno.land/x/oak@v12.6.1/mod.ts".into(),
+                    hash: Some(
+                        "c4e1b1c2d3a4b5f6e7d8c9b0a1f2e3d4c5b6a7f8e9d0c1b2a3f4e5d6c7b8a9f0".into()
+                    ),
+                },
+                Dependency::Url {
+                    url: "https://esm.sh/lodash-es@4.17.21".into(),
+                    hash: Some(
+                        "0a1b2c3d4e5f60718293a4b5c6d7e8f90a1b2c3d4e5f60718293a4b5c6d7e8f9".into()
+                    ),
+                },
+            ]),
+        );
+    }
+
+    #[test]
+    fn test_normalize_v4() {
+        let lockfile = indoc! {r#"
+          {
+            "version": "4",
+            "specifiers": {
+              "jsr:@std/path@1": "1.0.8",
+              "npm:@types/node@*": "22.5.4",
+              "npm:hono@^4.6.0": "4.6.3"
+            },
+            "jsr": {
+              "@std/path@1.0.8": {
+                "integrity": "..."
+              }
+            },
+            "npm": {
+              "@types/node@22.5.4": {
+                "integrity": "sha512-...",
+                "dependencies": [
+                  "undici-types"
+                ]
+              },
+              "hono@4.6.3": {
+                "integrity": "sha512-..."
+              },
+              "undici-types@6.19.8": {
+                "integrity": "sha512-..."
+              }
+            },
+            "workspace": {
+              "dependencies": [
+                "jsr:@std/path@1",
+                "npm:hono@^4.6.0"
+              ]
+            }
+          }
+        "#};
+
+        let result = normalize(lockfile).unwrap();
+        assert_eq!(
+            result,
+            HashSet::from([
+                Dependency::Jsr {
+                    scope: "std".into(),
+                    name: "path".into(),
+                    version: "1.0.8".into(),
+                },
+                Dependency::Npm {
+                    name: "@types/node".into(),
+                    version: "22.5.4".into(),
+                },
+                Dependency::Npm {
+                    name: "hono".into(),
+                    version: "4.6.3".into(),
+                },
+                Dependency::Npm {
+                    name: "undici-types".into(),
+                    version: "6.19.8".into(),
+                },
+            ]),
+        );
+    }
+}
@@ -0,0 +1,374 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::dependency::Dependency;
+
+use fancy_regex::Regex;
+use lazy_static::lazy_static;
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum Error {
+    #[error("Couldn't parse the lockfile.\n{message}")]
+    InvalidLockfileFormat { message: String },
+}
+
+impl Error {
+    fn invalid_line(file: &str, line_number: usize) -> Self {
+        Self::InvalidLockfileFormat {
+            message: format!("Unexpected syntax at {file} line {line_number}"),
+        }
+    }
+}
+
+// go.sum syntax:
+//
+// ```
+// <path> <version> h1:<hash>
+// <path> <version>/go.mod h1:<hash>
+// ```
+//
+// `/go.mod` lines only pin the go.mod file of a module, which is needed to build
+// the module graph. Modules actually downloaded always have the line without it.
+//
+// go.mod is optional. When given, `replace` and `exclude` directives are applied,
+// so modules replaced by another one or a local directory are dropped.
+// Requirements without their checksum in go.sum (e.g. before `go mod tidy`) can't be pinned,
+// and are skipped.
+//
+// Modules are emitted as `Dependency::GoModule` as is. `Dependency::canonicalize`
+// turns `github.com/<owner>/<name>` modules into `Dependency::GitHub`.
+
+type Module = (String, String);
+
+/// Commit hash of a pseudo-version (e.g. `v0.0.0-20230101120000-abcdef123456`).
+pub fn commit_hash(version: &str) -> Option<&str> {
+    lazy_static! {
+        static ref RE: Regex = Regex::new(
+            "^v[0-9]+\\.[0-9]+\\.[0-9]+-(?:[0-9A-Za-z.-]*[.-])?[0-9]{14}-(?<hash>[0-9a-f]{12})(?:\\+incompatible)?$",
+        ).unwrap();
+    }
+    let captures = RE.captures(version).unwrap()?;
+    captures.name("hash").map(|m| m.as_str())
+}
+
+fn parse_go_sum(value: &str) -> Result<HashSet<Module>, Error> {
+    let mut modules: HashSet<Module> = HashSet::new();
+
+    for (index, line) in value.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let [path, version, _hash] = fields.as_slice() else {
+            return Err(Error::invalid_line("go.sum", index + 1));
+        };
+        if version.ends_with("/go.mod") {
+            continue;
+        }
+        modules.insert((path.to_string(), version.to_string()));
+    }
+    Ok(modules)
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct GoMod {
+    requires: Vec<Module>,
+    excludes: HashSet<Module>,
+    /// `<path>( <version>)` to `<path>( <version>)`, where local replacements have no version
+    replaces: HashMap<(String, Option<String>), (String, Option<String>)>,
+}
+
+fn parse_go_mod(value: &str) -> Result<GoMod, Error> {
+    let mut go_mod = GoMod::default();
+    let mut block: Option<&str> = None;
+
+    for (index, line) in value.lines().enumerate() {
+        let line = line.split("//").next().unwrap().trim();
+        if line.is_empty() {
+            continue;
+        }
+        let (verb, args) = match block {
+            Some(_) if line == ")" => {
+                block = None;
+                continue;
+            }
+            Some(verb) => (verb, line),
+            None => line.split_once(char::is_whitespace).unwrap_or((line, "")),
+        };
+        let args = args.trim();
+        if args == "(" {
+            block = Some(verb);
+            continue;
+        }
+        let fields: Vec<String> = args
+            .split_whitespace()
+            .map(|field| field.trim_matches('"').to_owned())
+            .collect();
+        let invalid_line = || Error::invalid_line("go.mod", index + 1);
+        match verb {
+            "require" | "exclude" => {
+                let [path, version] = fields.as_slice() else {
+                    return Err(invalid_line());
+                };
+                let module = (path.to_owned(), version.to_owned());
+                if verb == "require" {
+                    go_mod.requires.push(module);
+                } else {
+                    go_mod.excludes.insert(module);
+                }
+            }
+            "replace" => {
+                let Some(arrow) = fields.iter().position(|field| field == "=>") else {
+                    return Err(invalid_line());
+                };
+                let (old, new) = (&fields[..arrow], &fields[arrow + 1..]);
+                let (Some(old_path), Some(new_path)) = (old.first(), new.first()) else {
+                    return Err(invalid_line());
+                };
+                go_mod.replaces.insert(
+                    (old_path.to_owned(), old.get(1).cloned()),
+                    (new_path.to_owned(), new.get(1).cloned()),
+                );
+            }
+            _ => {
+                // noop: module, go, toolchain, retract
+            }
+        }
+    }
+    Ok(go_mod)
+}
+
+impl GoMod {
+    /// The module actually used for a requirement, `None` if replaced with a local directory.
+    fn resolve(&self, path: &str, version: &str) -> Option<Module> {
+        let replacement = self
+            .replaces
+            .get(&(path.to_owned(), Some(version.to_owned())))
+            .or_else(|| self.replaces.get(&(path.to_owned(), None)));
+        match replacement {
+            Some((new_path, Some(new_version))) => {
+                Some((new_path.to_owned(), new_version.to_owned()))
+            }
+            Some((_, None)) => None,
+            None => Some((path.to_owned(), version.to_owned())),
+        }
+    }
+}
+
+fn into_dependencies(modules: HashSet<Module>) -> HashSet<Dependency> {
+    modules
+        .into_iter()
+        .map(|(path, version)| Dependency::GoModule { path, version })
+        .collect()
+}
+
+pub fn normalize(go_sum: &str) -> Result<HashSet<Dependency>, Error> {
+    let modules = parse_go_sum(go_sum)?;
+    Ok(into_dependencies(modules))
+}
+
+pub fn normalize_with_go_mod(go_sum: &str, go_mod: &str) -> Result<HashSet<Dependency>, Error> {
+    let mut modules = parse_go_sum(go_sum)?;
+    let go_mod = parse_go_mod(go_mod)?;
+
+    for (path, version) in &go_mod.requires {
+        let module = (path.to_owned(), version.to_owned());
+        if go_mod.resolve(path, version).as_ref() != Some(&module) {
+            // stale checksum of the replaced module
+            modules.remove(&module);
+        }
+    }
+    modules.retain(|module| !go_mod.excludes.contains(module));
+    Ok(into_dependencies(modules))
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    const GO_SUM: &str = indoc! {r#"
+        github.com/daangn/go-utils v1.2.0 h1:...
+        github.com/daangn/go-utils v1.2.0/go.mod h1:...
+        github.com/google/uuid v1.3.0 h1:...
+        github.com/google/uuid v1.3.0/go.mod h1:...
+        github.com/pkg/errors v0.9.1/go.mod h1:...
+        golang.org/x/sys v0.0.0-20230101120000-abcdef123456 h1:...
+        golang.org/x/sys v0.0.0-20230101120000-abcdef123456/go.mod h1:...
+        gopkg.in/yaml.v3 v3.0.0 h1:...
+        gopkg.in/yaml.v3 v3.0.1 h1:...
+        gopkg.in/yaml.v3 v3.0.1/go.mod h1:...
+    "#};
+
+    #[test]
+    fn test_normalize() {
+        let result = normalize(GO_SUM).unwrap();
+        assert_eq!(
+            result,
+            HashSet::from([
+                Dependency::GoModule {
+                    path: "github.com/daangn/go-utils".into(),
+                    version: "v1.2.0".into(),
+                },
+                Dependency::GoModule {
+                    path: "github.com/google/uuid".into(),
+                    version: "v1.3.0".into(),
+                },
+                Dependency::GoModule {
+                    path: "golang.org/x/sys".into(),
+                    version: "v0.0.0-20230101120000-abcdef123456".into(),
+                },
+                Dependency::GoModule {
+                    path: "gopkg.in/yaml.v3".into(),
+                    version: "v3.0.0".into(),
+                },
+                Dependency::GoModule {
+                    path: "gopkg.in/yaml.v3".into(),
+                    version: "v3.0.1".into(),
+                },
+            ]),
+        );
+    }
+
+    #[test]
+    fn test_normalize_with_go_mod() {
+        let go_mod = indoc! {r#"
+            module github.com/daangn/example
+
+            go 1.20
+
+            require (
+                github.com/daangn/go-utils v1.1.0
+                github.com/daangn/local-utils v0.0.0 // indirect
+                github.com/google/uuid v1.3.0
+                golang.org/x/sys v0.0.0-20230101120000-abcdef123456
+                gopkg.in/yaml.v3 v3.0.1
+            )
+
+            exclude gopkg.in/yaml.v3 v3.0.0
+
+            replace (
+                github.com/daangn/go-utils v1.1.0 => github.com/daangn/go-utils v1.2.0
+                github.com/daangn/local-utils => ../local-utils
+            )
+        "#};
+
+        let result = normalize_with_go_mod(GO_SUM, go_mod).unwrap();
+        assert_eq!(
+            result,
+            HashSet::from([
+                Dependency::GoModule {
+                    path: "github.com/daangn/go-utils".into(),
+                    version: "v1.2.0".into(),
+                },
+                Dependency::GoModule {
+                    path: "github.com/google/uuid".into(),
+                    version: "v1.3.0".into(),
+                },
+                Dependency::GoModule {
+                    path: "golang.org/x/sys".into(),
+                    version: "v0.0.0-20230101120000-abcdef123456".into(),
+                },
+                Dependency::GoModule {
+                    path: "gopkg.in/yaml.v3".into(),
+                    version: "v3.0.1".into(),
+                },
+            ]),
+        );
+    }
+
+    #[test]
+    fn test_normalize_with_go_mod_unpinned() {
+        let go_mod = indoc! {r#"
+            module github.com/daangn/example
+
+            require (
+                github.com/daangn/go-utils v1.2.0
+                github.com/google/uuid v1.3.0
+                github.com/pkg/errors v0.9.1
+            )
+
+            replace github.com/daangn/go-utils => ../go-utils
+        "#};
+        let go_sum = indoc! {r#"
+            github.com/daangn/go-utils v1.2.0 h1:...
+            github.com/daangn/go-utils v1.2.0/go.mod h1:...
+            github.com/google/uuid v1.3.0 h1:...
+            github.com/google/uuid v1.3.0/go.mod h1:...
+            github.com/pkg/errors v0.9.1/go.mod h1:...
+        "#};
+
+        let result = normalize_with_go_mod(go_sum, go_mod).unwrap();
+        assert_eq!(
+            result,
+            HashSet::from([Dependency::GoModule {
+                path: "github.com/google/uuid".into(),
+                version: "v1.3.0".into(),
+            }]),
+        );
+    }
+
+    #[test]
+    fn test_canonicalize() {
+        let canonicalize = |path: &str, version: &str| {
+            Dependency::GoModule {
+                path: path.into(),
+                version: version.into(),
+            }
+            .canonicalize()
+        };
+        assert_eq!(
+            canonicalize("github.com/google/uuid", "v1.3.0"),
+            Dependency::GitHub {
+                owner: "google".into(),
+                name: "uuid".into(),
+                head: Some("v1.3.0".into()),
+            },
+        );
+        assert_eq!(
+            canonicalize(
+                "github.com/daangn/go-utils/v2",
+                "v2.0.0-20230101120000-abcdef123456"
+            ),
+            Dependency::GitHub {
+                owner: "daangn".into(),
+                name: "go-utils".into(),
+                head: Some("abcdef123456".into()),
+            },
+        );
+        assert_eq!(
+            canonicalize("github.com/aws/aws-sdk-go-v2/service/s3", "v1.30.0"),
+            Dependency::GitHub {
+                owner: "aws".into(),
+                name: "aws-sdk-go-v2".into(),
+                head: Some("service/s3/v1.30.0".into()),
+            },
+        );
+        assert_eq!(
+            canonicalize("golang.org/x/sys", "v0.8.0"),
+            Dependency::GoModule {
+                path: "golang.org/x/sys".into(),
+                version: "v0.8.0".into(),
+            },
+        );
+    }
+
+    #[test]
+    fn test_commit_hash() {
+        assert_eq!(
+            commit_hash("v0.0.0-20230101120000-abcdef123456"),
+            Some("abcdef123456"),
+        );
+        assert_eq!(
+            commit_hash("v1.2.4-0.20230101120000-abcdef123456"),
+            Some("abcdef123456"),
+        );
+        assert_eq!(
+            commit_hash("v1.2.3-pre.0.20230101120000-abcdef123456+incompatible"),
+            Some("abcdef123456"),
+        );
+        assert_eq!(commit_hash("v1.2.3"), None);
+        assert_eq!(commit_hash("v1.2.3-rc.1"), None);
+    }
+}
@@ -0,0 +1,150 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::dependency::Dependency;
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum Error {
+    #[error("Couldn't parse the lockfile.\n{message}")]
+    InvalidLockfileFormat { message: String },
+}
+
+impl Error {
+    fn invalid_coordinate(line_number: usize, coordinate: &str) -> Self {
+        Self::InvalidLockfileFormat {
+            message: format!("Unsupported coordinate at line {line_number}: {coordinate}"),
+        }
+    }
+}
+
+// Lockfile syntax:
+//
+// ```
+// # This is a Gradle generated file for dependency locking.
+// <group>:<artifact>:<version>=<configuration>(,<configuration>)*
+// empty=<configuration>(,<configuration>)*
+// ```
+//
+// `empty=` lists the configurations without any dependency.
+// This applies to both `gradle.lockfile` and `buildscript-gradle.lockfile`.
+//
+// Legacy per-configuration lockfiles (`gradle/dependency-locks/<configuration>.lockfile`)
+// have coordinates only, so their configurations are left empty.
+
+/// Names of the configurations (e.g. `releaseRuntimeClasspath`) by each resolved artifact.
+pub type Configurations = HashMap<Dependency, BTreeSet<String>>;
+
+fn parse_coordinate(coordinate: &str) -> Option<Dependency> {
+    let mut parts = coordinate.split(':');
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(group_id), Some(artifact_id), Some(version), None)
+            if !group_id.is_empty() && !artifact_id.is_empty() && !version.is_empty() =>
+        {
+            Some(Dependency::Maven {
+                group_id: group_id.into(),
+                artifact_id: artifact_id.into(),
+                version: version.into(),
+            })
+        }
+        _ => None,
+    }
+}
+
+pub fn normalize_with_configurations(value: &str) -> Result<Configurations, Error> {
+    let mut deps: Configurations = HashMap::new();
+
+    for (index, line) in value.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (coordinate, configurations) = line.split_once('=').unwrap_or((line, ""));
+        if coordinate == "empty" {
+            continue;
+        }
+        let Some(dependency) = parse_coordinate(coordinate) else {
+            return Err(Error::invalid_coordinate(index + 1, coordinate));
+        };
+        deps.entry(dependency).or_default().extend(
+            configurations
+                .split(',')
+                .filter(|configuration| !configuration.is_empty())
+                .map(String::from),
+        );
+    }
+    Ok(deps)
+}
+
+pub fn normalize(value: &str) -> Result<HashSet<Dependency>, Error> {
+    let deps = normalize_with_configurations(value)?;
+    Ok(deps.into_keys().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    const LOCKFILE: &str = indoc! {r#"
+        # This is a Gradle generated file for dependency locking.
+        # Manual edits can break the build and are not advised.
+        # This file is expected to be part of source control.
+        androidx.activity:activity:1.7.2=debugRuntimeClasspath,releaseRuntimeClasspath
+        com.squareup.okhttp3:okhttp:4.11.0=debugRuntimeClasspath,releaseRuntimeClasspath,testRuntimeClasspath
+        junit:junit:4.13.2=testCompileClasspath,testRuntimeClasspath
+        empty=annotationProcessor,kapt
+    "#};
+
+    #[test]
+    fn test_normalize() {
+        let result = normalize(LOCKFILE).unwrap();
+        assert_eq!(
+            result,
+            HashSet::from([
+                Dependency::Maven {
+                    group_id: "androidx.activity".into(),
+                    artifact_id: "activity".into(),
+                    version: "1.7.2".into(),
+                },
+                Dependency::Maven {
+                    group_id: "com.squareup.okhttp3".into(),
+                    artifact_id: "okhttp".into(),
+                    version: "4.11.0".into(),
+                },
+                Dependency::Maven {
+                    group_id: "junit".into(),
+                    artifact_id: "junit".into(),
+                    version: "4.13.2".into(),
+                },
+            ]),
+        );
+    }
+
+    #[test]
+    fn test_normalize_with_configurations() {
+        let result = normalize_with_configurations(LOCKFILE).unwrap();
+        let junit = Dependency::Maven {
+            group_id: "junit".into(),
+            artifact_id: "junit".into(),
+            version: "4.13.2".into(),
+        };
+        assert_eq!(
+            result.get(&junit),
+            Some(&BTreeSet::from([
+                "testCompileClasspath".to_owned(),
+                "testRuntimeClasspath".to_owned(),
+            ])),
+        );
+    }
+
+    #[test]
+    fn test_normalize_invalid_coordinate() {
+        let lockfile = indoc! {r#"
+            # This is a Gradle generated file for dependency locking.
+            junit:junit=testCompileClasspath
+        "#};
+
+        let result = normalize(lockfile);
+        assert_eq!(result, Err(Error::invalid_coordinate(2, "junit:junit")));
+    }
+}
@@ -0,0 +1,170 @@
+use std::collections::HashSet;
+
+use crate::dependency::Dependency;
+
+use serde_yaml::{Mapping, Value};
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum Error {
+    #[error("Couldn't parse the lockfile.\n{message}")]
+    InvalidLockfileFormat { message: String },
+
+    #[error("Unsupported resolution: {resolution}")]
+    UnsupportedResolution { resolution: String },
+}
+
+impl From<serde_yaml::Error> for Error {
+    fn from(_error: serde_yaml::Error) -> Self {
+        Self::invalid_yaml()
+    }
+}
+
+impl Error {
+    fn invalid_yaml() -> Self {
+        Self::InvalidLockfileFormat {
+            message: "Not a valid YAML".into(),
+        }
+    }
+
+    fn invalid_format() -> Self {
+        Self::InvalidLockfileFormat {
+            message: "Malformed lockfile".into(),
+        }
+    }
+}
+
+// Lockfile layout (Chart.lock):
+//
+// `dependencies` is an array of `{ name, repository, version }`,
+// and `digest` is the hash of the whole dependencies of Chart.yaml.
+// The digest belongs to the lockfile rather than any chart, so it's returned next to them.
+//
+// "repository" is one of
+//
+// - URL of a chart repository (e.g. `https://charts.bitnami.com/bitnami`)
+// - OCI registry (e.g. `oci://registry-1.docker.io/bitnamicharts`)
+// - local chart directory (`file://<path>`), or empty for the charts in `charts/`
+// - name of a repository added to the local Helm (`@<name>`, `alias:<name>`)
+
+fn normalize_single_dependency(dependency: &Mapping) -> Result<Dependency, Error> {
+    let name = dependency.get("name").and_then(Value::as_str);
+    let version = dependency.get("version").and_then(Value::as_str);
+    let (Some(name), Some(version)) = (name, version) else {
+        return Err(Error::invalid_format());
+    };
+    let repository = dependency
+        .get("repository")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    // local charts and repositories only known to the local Helm are not supported
+    if !["https://", "http://", "oci://"]
+        .iter()
+        .any(|scheme| repository.starts_with(scheme))
+    {
+        return Err(Error::UnsupportedResolution {
+            resolution: repository.into(),
+        });
+    }
+    Ok(Dependency::HelmChart {
+        name: name.into(),
+        version: version.into(),
+        repository: repository.trim_end_matches('/').into(),
+    })
+}
+
+fn normalize_yaml(value: &Value) -> Result<HashSet<Dependency>, Error> {
+    let mut deps: HashSet<Dependency> = HashSet::new();
+
+    let Some(dependencies) = value.get("dependencies") else {
+        return Ok(deps);
+    };
+    let Some(dependencies) = dependencies.as_sequence() else {
+        return Err(Error::invalid_format());
+    };
+    for dependency in dependencies {
+        let Some(dependency) = dependency.as_mapping() else {
+            return Err(Error::invalid_format());
+        };
+        match normalize_single_dependency(dependency) {
+            Ok(dependency) => {
+                deps.insert(dependency);
+            }
+            Err(Error::UnsupportedResolution { .. }) => {
+                // noop
+            }
+            Err(error) => {
+                return Err(error);
+            }
+        }
+    }
+    Ok(deps)
+}
+
+/// Dependencies with the digest (e.g. `sha256:<hex>`) of the lockfile, if it has one.
+pub fn normalize_with_digest(value: &str) -> Result<(HashSet<Dependency>, Option<String>), Error> {
+    let yaml: Value = serde_yaml::from_str(value)?;
+    let digest = match yaml.get("digest") {
+        Some(digest) => Some(
+            digest
+                .as_str()
+                .ok_or_else(Error::invalid_format)?
+                .to_owned(),
+        ),
+        None => None,
+    };
+    Ok((normalize_yaml(&yaml)?, digest))
+}
+
+pub fn normalize(value: &str) -> Result<HashSet<Dependency>, Error> {
+    let (deps, _digest) = normalize_with_digest(value)?;
+    Ok(deps)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let lockfile = indoc! {r#"
+          dependencies:
+          - name: postgresql
+            repository: https://charts.bitnami.com/bitnami
+            version: 12.8.2
+          - name: common
+            repository: oci://registry-1.docker.io/bitnamicharts
+            version: 2.10.0
+          - name: karrot-common
+            repository: file://../karrot-common
+            version: 0.1.0
+          - name: redis
+            repository: '@bitnami'
+            version: 18.0.4
+          digest: sha256:4b1bc3b5a4e3ae1c5c3c1a7f0c0e2f6d2a9d6b9e7b8e5f1a3c0d2e4f6a8b0c1d
+          generated: "2023-09-20T12:00:00.000000+09:00"
+        "#};
+
+        let (result, digest) = normalize_with_digest(lockfile).unwrap();
+        assert_eq!(
+            digest.as_deref(),
+            Some("sha256:4b1bc3b5a4e3ae1c5c3c1a7f0c0e2f6d2a9d6b9e7b8e5f1a3c0d2e4f6a8b0c1d"),
+        );
+        assert_eq!(
+            result,
+            HashSet::from([
+                Dependency::HelmChart {
+                    name: "postgresql".into(),
+                    version: "12.8.2".into(),
+                    repository: "https://charts.bitnami.com/bitnami".into(),
+                },
+                Dependency::HelmChart {
+                    name: "common".into(),
+                    version: "2.10.0".into(),
+                    repository: "oci://registry-1.docker.io/bitnamicharts".into(),
+                },
+            ]),
+        );
+    }
+}
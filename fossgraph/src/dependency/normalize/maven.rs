@@ -0,0 +1,260 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::dependency::Dependency;
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum Error {
+    #[error("Couldn't parse the dependency list.\n{message}")]
+    InvalidOutputFormat { message: String },
+}
+
+impl Error {
+    fn invalid_coordinate(line_number: usize, coordinate: &str) -> Self {
+        Self::InvalidOutputFormat {
+            message: format!("Unsupported coordinate at line {line_number}: {coordinate}"),
+        }
+    }
+}
+
+// Output syntax of maven-dependency-plugin (written by `-DoutputFile`):
+//
+// `dependency:list`
+//
+// ```
+// The following files have been resolved:
+//    <coordinate>( (optional))( -- module <name>)
+// ```
+//
+// `dependency:tree -DoutputType=text`
+//
+// ```
+// <root coordinate>
+// +- <coordinate>
+// |  \- <coordinate>
+// \- (<coordinate> - omitted for duplicate)
+// ```
+//
+// `dependency:tree -DoutputType=dot`
+//
+// ```
+// digraph "<root coordinate>" {
+//     "<coordinate>" -> "<coordinate>" ;
+// }
+// ```
+//
+// "coordinate" follows form of `<group>:<artifact>:<packaging>(:<classifier>):<version>:<scope>`.
+// The root project has no scope, and is skipped.
+// Entries in parentheses are omitted from the resolution (only with `-Dverbose`), and skipped too.
+//
+// Multi-module builds append the output of each module to the same file.
+
+const SCOPES: [&str; 6] = ["compile", "provided", "runtime", "test", "system", "import"];
+
+/// How an artifact is resolved, beyond its coordinate.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Artifact {
+    pub packaging: String,
+    pub classifier: Option<String>,
+    pub scope: String,
+}
+
+/// Artifacts (e.g. the main jar, `linux-x86_64` classifier) by each resolved dependency.
+pub type Artifacts = HashMap<Dependency, BTreeSet<Artifact>>;
+
+fn parse_coordinate(coordinate: &str) -> Option<(Dependency, Artifact)> {
+    let parts: Vec<&str> = coordinate.split(':').collect();
+    let (group_id, artifact_id, packaging, classifier, version, scope) = match parts.as_slice() {
+        [g, a, p, v, s] => (g, a, p, None, v, s),
+        [g, a, p, c, v, s] => (g, a, p, Some(c), v, s),
+        _ => return None,
+    };
+    if parts.iter().any(|part| part.is_empty()) || !SCOPES.contains(scope) {
+        return None;
+    }
+    Some((
+        Dependency::Maven {
+            group_id: (*group_id).into(),
+            artifact_id: (*artifact_id).into(),
+            version: (*version).into(),
+        },
+        Artifact {
+            packaging: (*packaging).into(),
+            classifier: classifier.map(|classifier| (*classifier).into()),
+            scope: (*scope).into(),
+        },
+    ))
+}
+
+/// Coordinates of the text output, with their line numbers.
+fn text_coordinates(value: &str) -> Vec<(usize, &str)> {
+    let mut coordinates: Vec<(usize, &str)> = Vec::new();
+
+    for (index, line) in value.lines().enumerate() {
+        let entry = line.trim_start_matches([' ', '|', '+', '-', '\\']);
+        let prefix = &line[..line.len() - entry.len()];
+        // the root project of `dependency:tree`
+        if prefix.is_empty() {
+            continue;
+        }
+        let Some(coordinate) = entry.split_whitespace().next() else {
+            continue;
+        };
+        // omitted ones, or `none` of `dependency:list` without any dependency
+        if coordinate.starts_with('(') || coordinate == "none" {
+            continue;
+        }
+        coordinates.push((index + 1, coordinate));
+    }
+    coordinates
+}
+
+/// Coordinates of the DOT output, with their line numbers.
+fn dot_coordinates(value: &str) -> Vec<(usize, &str)> {
+    let mut coordinates: Vec<(usize, &str)> = Vec::new();
+
+    for (index, line) in value.lines().enumerate() {
+        // children are always on the right side
+        let Some((_parent, child)) = line.split_once("->") else {
+            continue;
+        };
+        let coordinate = child.trim().trim_end_matches(';').trim();
+        coordinates.push((index + 1, coordinate.trim_matches('"')));
+    }
+    coordinates
+}
+
+pub fn normalize_with_artifacts(value: &str) -> Result<Artifacts, Error> {
+    let mut deps: Artifacts = HashMap::new();
+
+    let coordinates = if value.trim_start().starts_with("digraph") {
+        dot_coordinates(value)
+    } else {
+        text_coordinates(value)
+    };
+    for (line_number, coordinate) in coordinates {
+        let Some((dependency, artifact)) = parse_coordinate(coordinate) else {
+            return Err(Error::invalid_coordinate(line_number, coordinate));
+        };
+        deps.entry(dependency).or_default().insert(artifact);
+    }
+    Ok(deps)
+}
+
+pub fn normalize(value: &str) -> Result<HashSet<Dependency>, Error> {
+    let deps = normalize_with_artifacts(value)?;
+    Ok(deps.into_keys().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    fn maven(group_id: &str, artifact_id: &str, version: &str) -> Dependency {
+        Dependency::Maven {
+            group_id: group_id.into(),
+            artifact_id: artifact_id.into(),
+            version: version.into(),
+        }
+    }
+
+    fn artifact(packaging: &str, classifier: Option<&str>, scope: &str) -> Artifact {
+        Artifact {
+            packaging: packaging.into(),
+            classifier: classifier.map(String::from),
+            scope: scope.into(),
+        }
+    }
+
+    #[test]
+    fn test_normalize_list() {
+        let output = indoc! {r#"
+
+          The following files have been resolved:
+             com.fasterxml.jackson.core:jackson-databind:jar:2.15.2:compile -- module com.fasterxml.jackson.databind
+             io.netty:netty-transport-native-epoll:jar:4.1.94.Final:runtime -- module io.netty.transport.epoll [auto]
+             io.netty:netty-transport-native-epoll:jar:linux-x86_64:4.1.94.Final:runtime -- module io.netty.transport.epoll [auto]
+             org.junit.jupiter:junit-jupiter:jar:5.9.3:test -- module org.junit.jupiter
+             org.projectlombok:lombok:jar:1.18.28:provided (optional) -- module lombok
+
+        "#};
+
+        let result = normalize_with_artifacts(output).unwrap();
+        assert_eq!(
+            result,
+            HashMap::from([
+                (
+                    maven("com.fasterxml.jackson.core", "jackson-databind", "2.15.2"),
+                    BTreeSet::from([artifact("jar", None, "compile")]),
+                ),
+                (
+                    maven("io.netty", "netty-transport-native-epoll", "4.1.94.Final"),
+                    BTreeSet::from([
+                        artifact("jar", None, "runtime"),
+                        artifact("jar", Some("linux-x86_64"), "runtime"),
+                    ]),
+                ),
+                (
+                    maven("org.junit.jupiter", "junit-jupiter", "5.9.3"),
+                    BTreeSet::from([artifact("jar", None, "test")]),
+                ),
+                (
+                    maven("org.projectlombok", "lombok", "1.18.28"),
+                    BTreeSet::from([artifact("jar", None, "provided")]),
+                ),
+            ]),
+        );
+    }
+
+    #[test]
+    fn test_normalize_tree() {
+        let text = indoc! {r#"
+          com.daangn:chat-api:jar:1.0.0-SNAPSHOT
+          +- org.springframework.boot:spring-boot-starter-web:jar:3.1.2:compile
+          |  +- org.springframework.boot:spring-boot-starter:jar:3.1.2:compile
+          |  |  \- org.yaml:snakeyaml:jar:1.33:compile
+          |  \- (org.yaml:snakeyaml:jar:1.33:compile - omitted for duplicate)
+          \- org.junit.jupiter:junit-jupiter:jar:5.9.3:test
+          com.daangn:chat-worker:jar:1.0.0-SNAPSHOT
+          \- org.yaml:snakeyaml:jar:2.0:compile
+        "#};
+        let dot = indoc! {r#"
+          digraph "com.daangn:chat-api:jar:1.0.0-SNAPSHOT" {
+          	"com.daangn:chat-api:jar:1.0.0-SNAPSHOT" -> "org.springframework.boot:spring-boot-starter-web:jar:3.1.2:compile" ;
+          	"com.daangn:chat-api:jar:1.0.0-SNAPSHOT" -> "org.junit.jupiter:junit-jupiter:jar:5.9.3:test" ;
+          	"org.springframework.boot:spring-boot-starter-web:jar:3.1.2:compile" -> "org.springframework.boot:spring-boot-starter:jar:3.1.2:compile" ;
+          	"org.springframework.boot:spring-boot-starter:jar:3.1.2:compile" -> "org.yaml:snakeyaml:jar:1.33:compile" ;
+           } digraph "com.daangn:chat-worker:jar:1.0.0-SNAPSHOT" {
+          	"com.daangn:chat-worker:jar:1.0.0-SNAPSHOT" -> "org.yaml:snakeyaml:jar:2.0:compile" ;
+           }
+        "#};
+        let expected = HashSet::from([
+            maven(
+                "org.springframework.boot",
+                "spring-boot-starter-web",
+                "3.1.2",
+            ),
+            maven("org.springframework.boot", "spring-boot-starter", "3.1.2"),
+            maven("org.yaml", "snakeyaml", "1.33"),
+            maven("org.yaml", "snakeyaml", "2.0"),
+            maven("org.junit.jupiter", "junit-jupiter", "5.9.3"),
+        ]);
+
+        assert_eq!(normalize(text).unwrap(), expected);
+        assert_eq!(normalize(dot).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_normalize_invalid() {
+        let output = indoc! {r#"
+          com.daangn:chat-api:jar:1.0.0-SNAPSHOT
+          \- org.yaml:snakeyaml:1.33
+        "#};
+
+        assert_eq!(
+            normalize(output),
+            Err(Error::invalid_coordinate(2, "org.yaml:snakeyaml:1.33")),
+        );
+    }
+}
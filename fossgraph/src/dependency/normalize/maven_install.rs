@@ -0,0 +1,302 @@
+use std::collections::HashSet;
+
+use crate::dependency::Dependency;
+
+use serde_json::Value;
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum Error {
+    #[error("Couldn't parse the lockfile.\n{message}")]
+    InvalidLockfileFormat { message: String },
+
+    #[error("Unsupported resolution: {resolution}")]
+    UnsupportedResolution { resolution: String },
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(_error: serde_json::Error) -> Self {
+        Self::invalid_json()
+    }
+}
+
+impl Error {
+    fn invalid_json() -> Self {
+        Self::InvalidLockfileFormat {
+            message: "Not a valid JSON".into(),
+        }
+    }
+
+    fn invalid_format() -> Self {
+        Self::InvalidLockfileFormat {
+            message: "Malformed lockfile".into(),
+        }
+    }
+
+    fn invalid_coordinate(coordinate: &str) -> Self {
+        Self::InvalidLockfileFormat {
+            message: format!("Some artifact has unsupported coordinate: {coordinate}"),
+        }
+    }
+}
+
+// Lockfile layout (maven_install.json of rules_jvm_external):
+//
+// version 2 has `artifacts` map of `<group>:<artifact>(:<packaging>)` keys to `{ shasums, version }`,
+// where `shasums` is keyed by classifiers (`jar` for the main artifact).
+//
+// Older ones have `dependency_tree.dependencies` array of `{ coord, sha256, url, ... }`,
+// where "coord" follows form of `<group>:<artifact>(:<packaging>(:<classifier>)):<version>`.
+//
+// Where each artifact is resolved from is `repositories` map of repository URLs to the keys
+// in version 2, and "url" of the artifact in older ones.
+
+const MAVEN_CENTRAL_REPOSITORIES: [&str; 2] = [
+    "https://repo1.maven.org/maven2/",
+    "https://repo.maven.apache.org/maven2/",
+];
+
+fn is_maven_central(url: &str) -> bool {
+    MAVEN_CENTRAL_REPOSITORIES
+        .iter()
+        .any(|repository| url.starts_with(repository) || url == repository.trim_end_matches('/'))
+}
+
+/// Keys of the artifacts resolved from Maven Central (version 2).
+fn parse_repositories(value: &Value) -> Result<HashSet<&str>, Error> {
+    let mut keys: HashSet<&str> = HashSet::new();
+
+    let Some(repositories) = value.get("repositories") else {
+        return Ok(keys);
+    };
+    let Some(repositories) = repositories.as_object() else {
+        return Err(Error::invalid_format());
+    };
+    for (url, artifacts) in repositories {
+        let Some(artifacts) = artifacts.as_array() else {
+            return Err(Error::invalid_format());
+        };
+        if is_maven_central(url) {
+            keys.extend(artifacts.iter().filter_map(Value::as_str));
+        }
+    }
+    Ok(keys)
+}
+
+fn normalize_artifact(key: &str, version: &str) -> Result<Dependency, Error> {
+    let mut parts = key.split(':');
+    match (parts.next(), parts.next()) {
+        (Some(group_id), Some(artifact_id)) if !group_id.is_empty() && !artifact_id.is_empty() => {
+            Ok(Dependency::Maven {
+                group_id: group_id.into(),
+                artifact_id: artifact_id.into(),
+                version: version.into(),
+            })
+        }
+        _ => Err(Error::invalid_coordinate(key)),
+    }
+}
+
+fn insert_dependency(
+    deps: &mut HashSet<Dependency>,
+    result: Result<Dependency, Error>,
+) -> Result<(), Error> {
+    match result {
+        Ok(dependency) => {
+            deps.insert(dependency);
+            Ok(())
+        }
+        Err(Error::UnsupportedResolution { .. }) => Ok(()),
+        Err(error) => Err(error),
+    }
+}
+
+fn normalize_json(value: Value) -> Result<HashSet<Dependency>, Error> {
+    let mut deps: HashSet<Dependency> = HashSet::new();
+
+    if let Some(artifacts) = value.get("artifacts") {
+        let Some(artifacts) = artifacts.as_object() else {
+            return Err(Error::invalid_format());
+        };
+        let central_keys = parse_repositories(&value)?;
+        for (key, artifact) in artifacts {
+            let Some(version) = artifact.get("version").and_then(Value::as_str) else {
+                return Err(Error::invalid_coordinate(key));
+            };
+            // private repository is not supported
+            let result = if central_keys.contains(key.as_str()) {
+                normalize_artifact(key, version)
+            } else {
+                Err(Error::UnsupportedResolution {
+                    resolution: key.into(),
+                })
+            };
+            insert_dependency(&mut deps, result)?;
+        }
+    } else if let Some(tree) = value.get("dependency_tree") {
+        let Some(dependencies) = tree.get("dependencies").and_then(Value::as_array) else {
+            return Err(Error::invalid_format());
+        };
+        for dependency in dependencies {
+            let Some(coordinate) = dependency.get("coord").and_then(Value::as_str) else {
+                return Err(Error::invalid_format());
+            };
+            let Some((key, version)) = coordinate.rsplit_once(':') else {
+                return Err(Error::invalid_coordinate(coordinate));
+            };
+            // private repository is not supported
+            let url = dependency.get("url").and_then(Value::as_str);
+            let result = match url {
+                Some(url) if is_maven_central(url) => normalize_artifact(key, version),
+                _ => Err(Error::UnsupportedResolution {
+                    resolution: url.unwrap_or(coordinate).into(),
+                }),
+            };
+            insert_dependency(&mut deps, result)?;
+        }
+    } else {
+        return Err(Error::invalid_format());
+    }
+    Ok(deps)
+}
+
+pub fn normalize(value: &str) -> Result<HashSet<Dependency>, Error> {
+    let json: Value = serde_json::from_str(value)?;
+    normalize_json(json)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let lockfile = indoc! {r#"
+          {
+            "__AUTOGENERATED_FILE_DO_NOT_MODIFY_THIS_FILE_MANUALLY": "THERE_IS_NO_DATA_ONLY_ZUUL",
+            "__INPUT_ARTIFACTS_HASH": -1206281154,
+            "__RESOLVED_ARTIFACTS_HASH": 1416386318,
+            "artifacts": {
+              "com.google.guava:failureaccess": {
+                "shasums": {
+                  "jar": "a171ee4c734dd2da837e4b16be9df4661afab72a41adaf31eb84dfdaf936ca26"
+                },
+                "version": "1.0.1"
+              },
+              "com.google.guava:guava": {
+                "shasums": {
+                  "jar": "3a6b4c6d5e7f8091a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6f70819",
+                  "sources": "..."
+                },
+                "version": "32.1.2-jre"
+              },
+              "io.netty:netty-transport-native-epoll": {
+                "shasums": {
+                  "jar": "...",
+                  "linux-x86_64": "..."
+                },
+                "version": "4.1.97.Final"
+              },
+              "com.daangn:karrot-bom": {
+                "shasums": {
+                  "jar": "..."
+                },
+                "version": "1.0.0"
+              }
+            },
+            "dependencies": {
+              "com.google.guava:guava": [
+                "com.google.guava:failureaccess"
+              ]
+            },
+            "repositories": {
+              "https://repo1.maven.org/maven2/": [
+                "com.google.guava:failureaccess",
+                "com.google.guava:guava",
+                "io.netty:netty-transport-native-epoll",
+                "io.netty:netty-transport-native-epoll:jar:linux-x86_64"
+              ],
+              "https://nexus.example.com/repository/maven-releases/": [
+                "com.daangn:karrot-bom"
+              ]
+            },
+            "version": "2"
+          }
+        "#};
+
+        let result = normalize(lockfile).unwrap();
+        assert_eq!(
+            result,
+            HashSet::from([
+                Dependency::Maven {
+                    group_id: "com.google.guava".into(),
+                    artifact_id: "failureaccess".into(),
+                    version: "1.0.1".into(),
+                },
+                Dependency::Maven {
+                    group_id: "com.google.guava".into(),
+                    artifact_id: "guava".into(),
+                    version: "32.1.2-jre".into(),
+                },
+                Dependency::Maven {
+                    group_id: "io.netty".into(),
+                    artifact_id: "netty-transport-native-epoll".into(),
+                    version: "4.1.97.Final".into(),
+                },
+            ]),
+        );
+    }
+
+    #[test]
+    fn test_normalize_dependency_tree() {
+        let lockfile = indoc! {r#"
+          {
+            "dependency_tree": {
+              "__AUTOGENERATED_FILE_DO_NOT_MODIFY_THIS_FILE_MANUALLY": "THERE_IS_NO_DATA_ONLY_ZUUL",
+              "__INPUT_ARTIFACTS_HASH": 1234,
+              "__RESOLVED_ARTIFACTS_HASH": 5678,
+              "conflict_resolution": {},
+              "dependencies": [
+                {
+                  "coord": "com.google.guava:guava:31.1-jre",
+                  "deps": [],
+                  "sha256": "...",
+                  "url": "https://repo1.maven.org/maven2/com/google/guava/guava/31.1-jre/guava-31.1-jre.jar"
+                },
+                {
+                  "coord": "io.netty:netty-transport-native-epoll:jar:linux-x86_64:4.1.79.Final",
+                  "deps": [],
+                  "sha256": "...",
+                  "url": "https://repo1.maven.org/maven2/io/netty/netty-transport-native-epoll/4.1.79.Final/netty-transport-native-epoll-4.1.79.Final-linux-x86_64.jar"
+                },
+                {
+                  "coord": "com.daangn:karrot-bom:1.0.0",
+                  "deps": [],
+                  "sha256": "...",
+                  "url": "https://nexus.example.com/repository/maven-releases/com/daangn/karrot-bom/1.0.0/karrot-bom-1.0.0.jar"
+                }
+              ],
+              "version": "0.1.0"
+            }
+          }
+        "#};
+
+        let result = normalize(lockfile).unwrap();
+        assert_eq!(
+            result,
+            HashSet::from([
+                Dependency::Maven {
+                    group_id: "com.google.guava".into(),
+                    artifact_id: "guava".into(),
+                    version: "31.1-jre".into(),
+                },
+                Dependency::Maven {
+                    group_id: "io.netty".into(),
+                    artifact_id: "netty-transport-native-epoll".into(),
+                    version: "4.1.79.Final".into(),
+                },
+            ]),
+        );
+    }
+}
@@ -0,0 +1,182 @@
+use std::collections::HashSet;
+
+use crate::dependency::Dependency;
+
+mod term;
+
+use term::Term;
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum Error {
+    #[error("Couldn't parse the lockfile.\n{message}")]
+    InvalidLockfileFormat { message: String },
+
+    #[error("Unsupported resolution: {resolution}")]
+    UnsupportedResolution { resolution: String },
+}
+
+impl Error {
+    fn invalid_term(position: usize) -> Self {
+        Self::InvalidLockfileFormat {
+            message: format!("Not a valid Elixir term at {position}"),
+        }
+    }
+
+    fn invalid_format() -> Self {
+        Self::InvalidLockfileFormat {
+            message: "Malformed lockfile".into(),
+        }
+    }
+
+    fn invalid_package(name: &str) -> Self {
+        Self::InvalidLockfileFormat {
+            message: format!("Some package has unsupported form: {name}"),
+        }
+    }
+}
+
+// Lockfile layout:
+//
+// A map of application names to a tuple, the first element of which is the SCM.
+//
+// - `{:hex, :<package>, "<version>", "<inner checksum>", [<build tools>], [<deps>], "<repo>", "<outer checksum>"}`
+// - `{:git, "<url>", "<commit>", [<options>]}`
+//
+// The package name can differ from the application name when the package is aliased.
+// Private packages have their organization in the repo (e.g. `hexpm:daangn`).
+
+fn normalize_single_package(name: &str, package: &Term) -> Result<Dependency, Error> {
+    let Term::Tuple(elements) = package else {
+        return Err(Error::invalid_package(name));
+    };
+    let scm = elements
+        .first()
+        .and_then(Term::as_atom)
+        .ok_or_else(|| Error::invalid_package(name))?;
+
+    match scm {
+        "hex" => {
+            let package_name = elements.get(1).and_then(Term::as_atom);
+            let version = elements.get(2).and_then(Term::as_str);
+            let (Some(package_name), Some(version)) = (package_name, version) else {
+                return Err(Error::invalid_package(name));
+            };
+            // old lockfiles have no repo
+            let repo = elements.get(6).and_then(Term::as_str).unwrap_or("hexpm");
+            Ok(Dependency::Hex {
+                name: package_name.into(),
+                version: version.into(),
+                repo: repo.into(),
+            })
+        }
+        "git" => {
+            let url = elements.get(1).and_then(Term::as_str);
+            let commit = elements.get(2).and_then(Term::as_str);
+            let (Some(url), Some(commit)) = (url, commit) else {
+                return Err(Error::invalid_package(name));
+            };
+            Ok(Dependency::Git {
+                url: url.into(),
+                head: Some(commit.into()),
+            }
+            .canonicalize())
+        }
+        // custom SCM is not supported
+        _ => Err(Error::UnsupportedResolution {
+            resolution: scm.into(),
+        }),
+    }
+}
+
+fn normalize_term(value: Term) -> Result<HashSet<Dependency>, Error> {
+    let mut deps: HashSet<Dependency> = HashSet::new();
+
+    let Term::Map(packages) = value else {
+        return Err(Error::invalid_format());
+    };
+    for (name, package) in &packages {
+        let (Term::Atom(name) | Term::String(name)) = name else {
+            return Err(Error::invalid_format());
+        };
+        match normalize_single_package(name, package) {
+            Ok(dependency) => {
+                deps.insert(dependency);
+            }
+            Err(Error::UnsupportedResolution { .. }) => {
+                // noop
+            }
+            Err(error) => {
+                return Err(error);
+            }
+        }
+    }
+    Ok(deps)
+}
+
+pub fn normalize(value: &str) -> Result<HashSet<Dependency>, Error> {
+    let term = term::parse(value)?;
+    normalize_term(term)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let lockfile = indoc! {r#"
+          %{
+            "castore": {:hex, :castore, "1.0.3", "7130ba6d24c8424014194676d608cb989f62ef8039efd50ff4b3f33286d06db8", [:mix], [], "hexpm", "680ab01ef5d15b161ed6a95449fac5c6b8f60055677a8e79acf01b27baa4390b"},
+            "jason": {:hex, :jason, "1.4.1", "af1504e35f629ddcdd6addb3513c3853991f694921b1b9368b0bd32beb9f1b63", [:mix], [{:decimal, "~> 1.0 or ~> 2.0", [hex: :decimal, repo: "hexpm", optional: true]}], "hexpm", "fbb01ecdfd565b56261302f7e1fcc27c4fb8f32d56eab74db621fc154604a7a1"},
+            "karrot_auth": {:git, "https://github.com/daangn/karrot_auth.git", "de5d97557a09ad61ae6ac48b1258b67d304660f0", [branch: "main"]},
+            "karrot_proto": {:hex, :karrot_proto, "0.4.0", "...", [:mix], [], "hexpm:daangn", "..."},
+            "phoenix": {:hex, :phoenix, "1.7.7", "4cc501d4d823015007ba3cdd9c41ecaaf2ffb619d6fb283199fa8ddba89191e0", [:mix], [{:castore, ">= 0.0.0", [hex: :castore, repo: "hexpm", optional: false]}, {:jason, "~> 1.0", [hex: :jason, repo: "hexpm", optional: true]}], "hexpm", "8966e15c395e5e37591b6ed0bd2ae7f48e961f0f60ac4c733f9566b519453085"},
+            "presence": {:git, "git@git.example.com:elixir/presence.git", "0123456789abcdef0123456789abcdef01234567", []},
+            "uuid": {:hex, :uuid_utils, "1.6.5", "bcc3f9ae0b4ef0a4e8a6b6b5ba4b8d0a5e3b6c7b4b0a2c3c5c0e6a4a9b3e2c1d", [:mix], [], "hexpm", "..."},
+          }
+        "#};
+
+        let result = normalize(lockfile).unwrap();
+        assert_eq!(
+            result,
+            HashSet::from([
+                Dependency::Hex {
+                    name: "castore".into(),
+                    version: "1.0.3".into(),
+                    repo: "hexpm".into(),
+                },
+                Dependency::Hex {
+                    name: "jason".into(),
+                    version: "1.4.1".into(),
+                    repo: "hexpm".into(),
+                },
+                Dependency::Hex {
+                    name: "karrot_proto".into(),
+                    version: "0.4.0".into(),
+                    repo: "hexpm:daangn".into(),
+                },
+                Dependency::Hex {
+                    name: "phoenix".into(),
+                    version: "1.7.7".into(),
+                    repo: "hexpm".into(),
+                },
+                Dependency::Hex {
+                    name: "uuid_utils".into(),
+                    version: "1.6.5".into(),
+                    repo: "hexpm".into(),
+                },
+                Dependency::GitHub {
+                    owner: "daangn".into(),
+                    name: "karrot_auth".into(),
+                    head: Some("de5d97557a09ad61ae6ac48b1258b67d304660f0".into()),
+                },
+                Dependency::Git {
+                    url: "git@git.example.com:elixir/presence.git".into(),
+                    head: Some("0123456789abcdef0123456789abcdef01234567".into()),
+                },
+            ]),
+        );
+    }
+}
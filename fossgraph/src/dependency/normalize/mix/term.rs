@@ -0,0 +1,271 @@
+use super::Error;
+
+// Term syntax (a subset of Elixir literals used by mix.lock):
+//
+// - map: `%{<key> => <value>, "<key>": <value>}`
+// - tuple: `{<term>, ...}`
+// - list: `[<term>, ...]`, keyword list: `[<key>: <value>, ...]`
+// - atom: `:name`, `:"name"`, `true`, `false`, `nil`
+// - string: `"..."`
+// - integer: `123`
+//
+// Keyword pairs (`key: value`) are `{:key, value}` tuples as in Elixir.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum Term {
+    Atom(String),
+    String(String),
+    Integer(i64),
+    List(Vec<Term>),
+    Tuple(Vec<Term>),
+    Map(Vec<(Term, Term)>),
+}
+
+impl Term {
+    pub(super) fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub(super) fn as_atom(&self) -> Option<&str> {
+        match self {
+            Self::Atom(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.input[self.position..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start();
+            self.position += rest.len() - trimmed.len();
+            // comments
+            if trimmed.starts_with('#') {
+                self.position += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), Error> {
+        self.skip_whitespace();
+        if !self.rest().starts_with(token) {
+            return Err(Error::invalid_term(self.position));
+        }
+        self.position += token.len();
+        Ok(())
+    }
+
+    fn parse_identifier(&mut self) -> &'a str {
+        let rest = self.rest();
+        let end = rest
+            .find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '?' | '!' | '@')))
+            .unwrap_or(rest.len());
+        self.position += end;
+        &rest[..end]
+    }
+
+    fn parse_string(&mut self) -> Result<String, Error> {
+        self.expect("\"")?;
+        let mut value = String::new();
+        let mut chars = self.rest().char_indices();
+        while let Some((index, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.position += index + 1;
+                    return Ok(value);
+                }
+                '\\' => match chars.next() {
+                    Some((_, 'n')) => value.push('\n'),
+                    Some((_, 't')) => value.push('\t'),
+                    Some((_, escaped)) => value.push(escaped),
+                    None => break,
+                },
+                _ => value.push(c),
+            }
+        }
+        Err(Error::invalid_term(self.input.len()))
+    }
+
+    /// Parses `<key>:` of keyword pairs, or returns `None` without consuming anything.
+    fn parse_keyword_key(&mut self) -> Result<Option<String>, Error> {
+        self.skip_whitespace();
+        let start = self.position;
+        let key = match self.peek() {
+            Some('"') => self.parse_string()?,
+            Some(c) if c.is_alphabetic() || c == '_' => self.parse_identifier().to_owned(),
+            _ => return Ok(None),
+        };
+        if self.rest().starts_with(": ") || self.rest().starts_with(":\n") {
+            self.position += 1;
+            return Ok(Some(key));
+        }
+        self.position = start;
+        Ok(None)
+    }
+
+    fn parse_elements(&mut self, close: &str) -> Result<Vec<Term>, Error> {
+        let mut elements: Vec<Term> = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with(close) {
+                self.position += close.len();
+                return Ok(elements);
+            }
+            let element = match self.parse_keyword_key()? {
+                Some(key) => Term::Tuple(vec![Term::Atom(key), self.parse_term()?]),
+                None => self.parse_term()?,
+            };
+            elements.push(element);
+            self.skip_whitespace();
+            if self.rest().starts_with(',') {
+                self.position += 1;
+            } else if !self.rest().starts_with(close) {
+                return Err(Error::invalid_term(self.position));
+            }
+        }
+    }
+
+    fn parse_map(&mut self) -> Result<Term, Error> {
+        self.expect("%{")?;
+        let mut entries: Vec<(Term, Term)> = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with('}') {
+                self.position += 1;
+                return Ok(Term::Map(entries));
+            }
+            let entry = match self.parse_keyword_key()? {
+                Some(key) => (Term::Atom(key), self.parse_term()?),
+                None => {
+                    let key = self.parse_term()?;
+                    self.expect("=>")?;
+                    (key, self.parse_term()?)
+                }
+            };
+            entries.push(entry);
+            self.skip_whitespace();
+            if self.rest().starts_with(',') {
+                self.position += 1;
+            } else if !self.rest().starts_with('}') {
+                return Err(Error::invalid_term(self.position));
+            }
+        }
+    }
+
+    fn parse_term(&mut self) -> Result<Term, Error> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('%') => self.parse_map(),
+            Some('{') => {
+                self.position += 1;
+                Ok(Term::Tuple(self.parse_elements("}")?))
+            }
+            Some('[') => {
+                self.position += 1;
+                Ok(Term::List(self.parse_elements("]")?))
+            }
+            Some('"') => Ok(Term::String(self.parse_string()?)),
+            Some(':') => {
+                self.position += 1;
+                match self.peek() {
+                    Some('"') => Ok(Term::Atom(self.parse_string()?)),
+                    _ => match self.parse_identifier() {
+                        "" => Err(Error::invalid_term(self.position)),
+                        atom => Ok(Term::Atom(atom.into())),
+                    },
+                }
+            }
+            Some(c) if c.is_ascii_digit() || c == '-' => {
+                let start = self.position;
+                self.position += 1;
+                let rest = self.rest();
+                let end = rest
+                    .find(|c: char| !(c.is_ascii_digit() || c == '_'))
+                    .unwrap_or(rest.len());
+                self.position += end;
+                self.input[start..self.position]
+                    .replace('_', "")
+                    .parse()
+                    .map(Term::Integer)
+                    .map_err(|_| Error::invalid_term(start))
+            }
+            Some(c) if c.is_alphabetic() => match self.parse_identifier() {
+                atom @ ("true" | "false" | "nil") => Ok(Term::Atom(atom.into())),
+                _ => Err(Error::invalid_term(self.position)),
+            },
+            _ => Err(Error::invalid_term(self.position)),
+        }
+    }
+}
+
+pub(super) fn parse(input: &str) -> Result<Term, Error> {
+    let mut parser = Parser { input, position: 0 };
+    let term = parser.parse_term()?;
+    parser.skip_whitespace();
+    if parser.position != input.len() {
+        return Err(Error::invalid_term(parser.position));
+    }
+    Ok(term)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let input = r#"%{"a": {:hex, :a, "1.0.0", [:mix], [{:b, "~> 1.0", [hex: :b, optional: false]}], 42}, "c" => nil}"#;
+        assert_eq!(
+            parse(input),
+            Ok(Term::Map(vec![
+                (
+                    Term::Atom("a".into()),
+                    Term::Tuple(vec![
+                        Term::Atom("hex".into()),
+                        Term::Atom("a".into()),
+                        Term::String("1.0.0".into()),
+                        Term::List(vec![Term::Atom("mix".into())]),
+                        Term::List(vec![Term::Tuple(vec![
+                            Term::Atom("b".into()),
+                            Term::String("~> 1.0".into()),
+                            Term::List(vec![
+                                Term::Tuple(vec![Term::Atom("hex".into()), Term::Atom("b".into())]),
+                                Term::Tuple(vec![
+                                    Term::Atom("optional".into()),
+                                    Term::Atom("false".into()),
+                                ]),
+                            ]),
+                        ])]),
+                        Term::Integer(42),
+                    ]),
+                ),
+                (Term::String("c".into()), Term::Atom("nil".into())),
+            ])),
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert_eq!(parse(r#"%{"a" => }"#), Err(Error::invalid_term(9)));
+        assert_eq!(parse(r#"{:a, :b"#), Err(Error::invalid_term(7)));
+    }
+}
@@ -0,0 +1,381 @@
+use std::collections::{HashSet, VecDeque};
+
+use crate::dependency::Dependency;
+
+use serde_json::{Map, Value};
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum Error {
+    #[error("Couldn't parse the lockfile.\n{message}")]
+    InvalidLockfileFormat { message: String },
+
+    #[error("Unsupported resolution: {resolution}")]
+    UnsupportedResolution { resolution: String },
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(_error: serde_json::Error) -> Self {
+        Self::invalid_json()
+    }
+}
+
+impl Error {
+    fn invalid_json() -> Self {
+        Self::InvalidLockfileFormat {
+            message: "Not a valid JSON".into(),
+        }
+    }
+
+    fn invalid_format() -> Self {
+        Self::InvalidLockfileFormat {
+            message: "Malformed lockfile".into(),
+        }
+    }
+
+    fn invalid_node(name: &str) -> Self {
+        Self::InvalidLockfileFormat {
+            message: format!("Some node has unsupported form: {name}"),
+        }
+    }
+
+    fn invalid_input(input: &Value) -> Self {
+        Self::InvalidLockfileFormat {
+            message: format!("Some input refers to unknown node: {input}"),
+        }
+    }
+}
+
+// Lockfile layout:
+//
+// `nodes` maps node names to `{ inputs, locked, original, flake }`, and `root` is the name of root node.
+//
+// "inputs" maps input names to either a node name, or a path of input names from the root node
+// (e.g. `["nixpkgs"]`, `["flake-utils", "systems"]`) when it follows another input.
+//
+// "locked" has `type` and attributes by it.
+//
+// - `github`, `gitlab`: `owner`, `repo`, `rev`, optional `host`
+// - `git`: `url`, `rev`
+// - `tarball`, `file`: `url` (usually immutable one), `narHash`
+// - `path`, `indirect`, etc.: not supported
+//
+// Nodes no longer reachable from the root can remain in the lockfile, so they are skipped.
+
+fn get_str<'a>(locked: &'a Map<String, Value>, key: &str, name: &str) -> Result<&'a str, Error> {
+    locked
+        .get(key)
+        .and_then(Value::as_str)
+        .ok_or_else(|| Error::invalid_node(name))
+}
+
+fn normalize_single_node(name: &str, node: &Map<String, Value>) -> Result<Dependency, Error> {
+    let Some(locked) = node.get("locked").and_then(Value::as_object) else {
+        return Err(Error::invalid_node(name));
+    };
+    let locked_type = get_str(locked, "type", name)?;
+    match locked_type {
+        "github" | "gitlab" => {
+            let owner = get_str(locked, "owner", name)?;
+            let repo = get_str(locked, "repo", name)?;
+            let rev = get_str(locked, "rev", name)?;
+            let default_host = if locked_type == "github" {
+                "github.com"
+            } else {
+                "gitlab.com"
+            };
+            let host = locked
+                .get("host")
+                .and_then(Value::as_str)
+                .unwrap_or(default_host);
+            Ok(Dependency::Git {
+                url: format!("https://{host}/{owner}/{repo}"),
+                head: Some(rev.into()),
+            }
+            .canonicalize())
+        }
+        "git" => {
+            let url = get_str(locked, "url", name)?;
+            let rev = get_str(locked, "rev", name)?;
+            Ok(Dependency::Git {
+                url: url.into(),
+                head: Some(rev.into()),
+            }
+            .canonicalize())
+        }
+        "tarball" | "file" => {
+            let url = get_str(locked, "url", name)?;
+            let nar_hash = get_str(locked, "narHash", name)?;
+            Ok(Dependency::Url {
+                url: url.into(),
+                hash: Some(nar_hash.into()),
+            })
+        }
+        _ => Err(Error::UnsupportedResolution {
+            resolution: locked_type.into(),
+        }),
+    }
+}
+
+/// Resolves an input to the node name.
+fn resolve_input<'a>(
+    nodes: &'a Map<String, Value>,
+    root: &'a str,
+    input: &'a Value,
+    depth: usize,
+) -> Result<&'a str, Error> {
+    // follows can't be nested deeper than the number of nodes, unless it's cyclic
+    if depth > nodes.len() {
+        return Err(Error::invalid_input(input));
+    }
+    match input {
+        Value::String(name) => Ok(name),
+        Value::Array(path) => {
+            let mut name = root;
+            for segment in path {
+                let input = segment
+                    .as_str()
+                    .and_then(|segment| nodes.get(name)?.get("inputs")?.get(segment))
+                    .ok_or_else(|| Error::invalid_input(input))?;
+                name = resolve_input(nodes, root, input, depth + 1)?;
+            }
+            Ok(name)
+        }
+        _ => Err(Error::invalid_input(input)),
+    }
+}
+
+fn normalize_json(value: Value) -> Result<HashSet<Dependency>, Error> {
+    let mut deps: HashSet<Dependency> = HashSet::new();
+
+    let Some(nodes) = value.get("nodes").and_then(Value::as_object) else {
+        return Err(Error::invalid_format());
+    };
+    let root = value.get("root").and_then(Value::as_str).unwrap_or("root");
+
+    let mut visited: HashSet<&str> = HashSet::from([root]);
+    let mut queue: VecDeque<&str> = VecDeque::from([root]);
+    while let Some(name) = queue.pop_front() {
+        let Some(node) = nodes.get(name).and_then(Value::as_object) else {
+            return Err(Error::invalid_node(name));
+        };
+        if name != root {
+            match normalize_single_node(name, node) {
+                Ok(dependency) => {
+                    deps.insert(dependency);
+                }
+                Err(Error::UnsupportedResolution { .. }) => {
+                    // noop
+                }
+                Err(error) => {
+                    return Err(error);
+                }
+            }
+        }
+        let Some(inputs) = node.get("inputs") else {
+            continue;
+        };
+        let Some(inputs) = inputs.as_object() else {
+            return Err(Error::invalid_node(name));
+        };
+        for input in inputs.values() {
+            let input = resolve_input(nodes, root, input, 0)?;
+            if visited.insert(input) {
+                queue.push_back(input);
+            }
+        }
+    }
+    Ok(deps)
+}
+
+pub fn normalize(value: &str) -> Result<HashSet<Dependency>, Error> {
+    let json: Value = serde_json::from_str(value)?;
+    normalize_json(json)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let lockfile = indoc! {r#"
+          {
+            "nodes": {
+              "flake-utils": {
+                "inputs": {
+                  "systems": "systems"
+                },
+                "locked": {
+                  "lastModified": 1694529238,
+                  "narHash": "sha256-zsNZZGTGnMOf9YpHKJqMSsa0dXbfmxeoJ7xHlrt+xmY=",
+                  "owner": "numtide",
+                  "repo": "flake-utils",
+                  "rev": "ff7b65b44d01cf9ba6a71320833626af21126384",
+                  "type": "github"
+                },
+                "original": {
+                  "owner": "numtide",
+                  "repo": "flake-utils",
+                  "type": "github"
+                }
+              },
+              "karrot-tools": {
+                "inputs": {
+                  "nixpkgs": ["nixpkgs"]
+                },
+                "locked": {
+                  "lastModified": 1696000000,
+                  "narHash": "sha256-...",
+                  "ref": "refs/heads/main",
+                  "rev": "0123456789abcdef0123456789abcdef01234567",
+                  "revCount": 42,
+                  "type": "git",
+                  "url": "ssh://git@git.example.com/infra/karrot-tools"
+                },
+                "original": {
+                  "type": "git",
+                  "url": "ssh://git@git.example.com/infra/karrot-tools"
+                }
+              },
+              "local": {
+                "locked": {
+                  "lastModified": 1696000000,
+                  "narHash": "sha256-...",
+                  "path": "/home/karrot/local",
+                  "type": "path"
+                },
+                "original": {
+                  "path": "/home/karrot/local",
+                  "type": "path"
+                }
+              },
+              "nixpkgs": {
+                "locked": {
+                  "lastModified": 1695830400,
+                  "narHash": "sha256-b1SIesYjtjXp0RxP0Ivsv9m1Z0gBvAYdxuKxbcMvy2I=",
+                  "owner": "NixOS",
+                  "repo": "nixpkgs",
+                  "rev": "9cfaa8a1a00830d17487cb60a19bb86f96f09b27",
+                  "type": "github"
+                },
+                "original": {
+                  "owner": "NixOS",
+                  "ref": "nixos-unstable",
+                  "repo": "nixpkgs",
+                  "type": "github"
+                }
+              },
+              "nixpkgs_2": {
+                "locked": {
+                  "lastModified": 1690000000,
+                  "narHash": "sha256-...",
+                  "owner": "NixOS",
+                  "repo": "nixpkgs",
+                  "rev": "5e4c2ada4fcd54b99d56d7bd62f384511a7e2593",
+                  "type": "github"
+                },
+                "original": {
+                  "owner": "NixOS",
+                  "repo": "nixpkgs",
+                  "type": "github"
+                }
+              },
+              "poetry2nix": {
+                "inputs": {
+                  "flake-utils": ["flake-utils"],
+                  "nixpkgs": ["karrot-tools", "nixpkgs"]
+                },
+                "locked": {
+                  "lastModified": 1695000000,
+                  "narHash": "sha256-...",
+                  "owner": "nix-community",
+                  "repo": "poetry2nix",
+                  "rev": "4eb2ac54029af42a001c9901194e9ce19cbd8a40",
+                  "type": "gitlab"
+                },
+                "original": {
+                  "owner": "nix-community",
+                  "repo": "poetry2nix",
+                  "type": "gitlab"
+                }
+              },
+              "root": {
+                "inputs": {
+                  "flake-utils": "flake-utils",
+                  "karrot-tools": "karrot-tools",
+                  "local": "local",
+                  "nixpkgs": "nixpkgs",
+                  "poetry2nix": "poetry2nix",
+                  "rust-overlay": "rust-overlay"
+                }
+              },
+              "rust-overlay": {
+                "flake": false,
+                "locked": {
+                  "narHash": "sha256-Yd2WD2pqsLzFhG6K9k7gMqgBAm1nYp6pSbmQ5VNbMxk=",
+                  "type": "tarball",
+                  "url": "https://github.com/oxalica/rust-overlay/archive/0a9e6ab8a4a1e3f1a3a3b5c6d7e8f90a1b2c3d4e.tar.gz"
+                },
+                "original": {
+                  "type": "tarball",
+                  "url": "https://github.com/oxalica/rust-overlay/archive/master.tar.gz"
+                }
+              },
+              "systems": {
+                "locked": {
+                  "lastModified": 1681028828,
+                  "narHash": "sha256-Vy1rq5AaRuLzOxct8nz4T6wlgyUR7zLU309k9mBC768=",
+                  "owner": "nix-systems",
+                  "repo": "default",
+                  "rev": "da67096a3b9bf56a91d16901293e51ba5b49a27e",
+                  "type": "github"
+                },
+                "original": {
+                  "owner": "nix-systems",
+                  "repo": "default",
+                  "type": "github"
+                }
+              }
+            },
+            "root": "root",
+            "version": 7
+          }
+        "#};
+
+        let result = normalize(lockfile).unwrap();
+        assert_eq!(
+            result,
+            HashSet::from([
+                Dependency::GitHub {
+                    owner: "numtide".into(),
+                    name: "flake-utils".into(),
+                    head: Some("ff7b65b44d01cf9ba6a71320833626af21126384".into()),
+                },
+                Dependency::Git {
+                    url: "git@git.example.com:infra/karrot-tools".into(),
+                    head: Some("0123456789abcdef0123456789abcdef01234567".into()),
+                },
+                Dependency::GitHub {
+                    owner: "NixOS".into(),
+                    name: "nixpkgs".into(),
+                    head: Some("9cfaa8a1a00830d17487cb60a19bb86f96f09b27".into()),
+                },
+                Dependency::Git {
+                    url: "https://gitlab.com/nix-community/poetry2nix".into(),
+                    head: Some("4eb2ac54029af42a001c9901194e9ce19cbd8a40".into()),
+                },
+                Dependency::Url {
+                    url: "https://github.com/oxalica/rust-overlay/archive/0a9e6ab8a4a1e3f1a3a3b5c6d7e8f90a1b2c3d4e.tar.gz".into(),
+                    hash: Some("sha256-Yd2WD2pqsLzFhG6K9k7gMqgBAm1nYp6pSbmQ5VNbMxk=".into()),
+                },
+                Dependency::GitHub {
+                    owner: "nix-systems".into(),
+                    name: "default".into(),
+                    head: Some("da67096a3b9bf56a91d16901293e51ba5b49a27e".into()),
+                },
+            ]),
+        );
+    }
+}
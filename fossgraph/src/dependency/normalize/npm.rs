@@ -0,0 +1,458 @@
+use std::collections::HashSet;
+
+use crate::dependency::Dependency;
+
+use percent_encoding::percent_decode_str;
+use serde_json::{Map, Value};
+use url::Url;
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum Error {
+    #[error("Couldn't parse the lockfile.\n{message}")]
+    InvalidLockfileFormat { message: String },
+
+    #[error("Unsupported resolution: {resolution}")]
+    UnsupportedResolution { resolution: String },
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(_error: serde_json::Error) -> Self {
+        Self::invalid_json()
+    }
+}
+
+impl Error {
+    fn invalid_json() -> Self {
+        Self::InvalidLockfileFormat {
+            message: "Not a valid JSON".into(),
+        }
+    }
+
+    fn invalid_format() -> Self {
+        Self::InvalidLockfileFormat {
+            message: "Malformed lockfile".into(),
+        }
+    }
+
+    fn invalid_package(key: &str) -> Self {
+        Self::InvalidLockfileFormat {
+            message: format!("Some package has no version: {key}"),
+        }
+    }
+}
+
+// Lockfile layout:
+//
+// lockfileVersion 1 has a nested `dependencies` tree keyed by package name.
+// lockfileVersion 2 has both the legacy tree and the flat `packages` map.
+// lockfileVersion 3 has only the flat `packages` map.
+//
+// Keys of the `packages` map are install paths (e.g. `node_modules/a/node_modules/@b/c`).
+// The root project is `""`, and workspace members are keyed by their directory.
+//
+// "version" is usually a fixed version, but lockfileVersion 1 stores
+// aliases (`npm:<name>@<version>`) and git resolutions in it as well.
+
+pub(super) fn is_git_resolution(resolution: &str) -> bool {
+    ["git+", "git:", "git@", "github:", "ssh://"]
+        .iter()
+        .any(|prefix| resolution.starts_with(prefix))
+}
+
+pub(super) fn normalize_git_resolution(resolution: &str) -> Dependency {
+    let (url, head) = match resolution.split_once('#') {
+        Some((url, head)) => (url, Some(head.to_owned())),
+        None => (resolution, None),
+    };
+    if let Some(substr) = url.strip_prefix("github:") {
+        if let Some((owner, name)) = substr.split_once('/') {
+            return Dependency::GitHub {
+                owner: owner.into(),
+                name: name.into(),
+                head,
+            };
+        }
+    }
+    Dependency::Git {
+        url: url.into(),
+        head,
+    }
+    .canonicalize()
+}
+
+/// Whether the tarball URL follows the public registry convention
+/// (`<registry>/<name>/-/<basename>-<version>.tgz`).
+pub(super) fn is_registry_tarball(name: &str, version: &str, resolved: &str) -> bool {
+    let Ok(url) = Url::parse(resolved) else {
+        return false;
+    };
+    let path = percent_decode_str(url.path()).decode_utf8_lossy();
+    let basename = name.rsplit('/').next().unwrap();
+    path.ends_with(format!("/{name}/-/{basename}-{version}.tgz").as_str())
+}
+
+/// GitHub tarballs are pinned to a commit
+/// (`https://codeload.github.com/<owner>/<name>/tar.gz/<commit>`).
+pub(super) fn normalize_codeload_tarball(tarball: &str) -> Option<Dependency> {
+    let substr = tarball.strip_prefix("https://codeload.github.com/")?;
+    let segments: Vec<&str> = substr.split('/').collect();
+    match segments.as_slice() {
+        [owner, name, _archive, commit] => Some(Dependency::GitHub {
+            owner: (*owner).into(),
+            name: (*name).into(),
+            head: Some((*commit).into()),
+        }),
+        _ => None,
+    }
+}
+
+fn normalize_single_package(
+    name: &str,
+    version: &str,
+    resolved: Option<&str>,
+) -> Result<Dependency, Error> {
+    if is_git_resolution(version) {
+        return Ok(normalize_git_resolution(version));
+    }
+    // lockfileVersion 1 stores local and remote tarball dependencies in version
+    if ["file:", "link:", "http://", "https://"]
+        .iter()
+        .any(|prefix| version.starts_with(prefix))
+    {
+        return Err(Error::UnsupportedResolution {
+            resolution: version.into(),
+        });
+    }
+    let (name, version) = match version.strip_prefix("npm:") {
+        Some(alias) => match alias.get(1..).and_then(|substr| substr.split_once('@')) {
+            Some((_, version)) => (&alias[..alias.len() - version.len() - 1], version),
+            None => return Err(Error::invalid_package(name)),
+        },
+        None => (name, version),
+    };
+    match resolved {
+        Some(resolved) if is_git_resolution(resolved) => Ok(normalize_git_resolution(resolved)),
+        // private/custom registry, local tarball and `file:` links are not supported
+        Some(resolved) if !is_registry_tarball(name, version, resolved) => {
+            Err(Error::UnsupportedResolution {
+                resolution: resolved.into(),
+            })
+        }
+        _ => Ok(Dependency::Npm {
+            name: name.into(),
+            version: version.into(),
+        }),
+    }
+}
+
+fn insert_dependency(
+    deps: &mut HashSet<Dependency>,
+    result: Result<Dependency, Error>,
+) -> Result<(), Error> {
+    match result {
+        Ok(dependency) => {
+            deps.insert(dependency);
+            Ok(())
+        }
+        Err(Error::UnsupportedResolution { .. }) => Ok(()),
+        Err(error) => Err(error),
+    }
+}
+
+fn normalize_packages(
+    packages: &Map<String, Value>,
+    deps: &mut HashSet<Dependency>,
+) -> Result<(), Error> {
+    for (key, value) in packages {
+        // skip the root project and workspace members
+        let Some((_, install_name)) = key.rsplit_once("node_modules/") else {
+            continue;
+        };
+        let Some(package) = value.as_object() else {
+            return Err(Error::invalid_format());
+        };
+        if package.get("link").and_then(Value::as_bool) == Some(true) {
+            continue;
+        }
+        let name = package
+            .get("name")
+            .and_then(Value::as_str)
+            .unwrap_or(install_name);
+        let version = package
+            .get("version")
+            .and_then(Value::as_str)
+            .ok_or_else(|| Error::invalid_package(key))?;
+        let resolved = package.get("resolved").and_then(Value::as_str);
+        insert_dependency(deps, normalize_single_package(name, version, resolved))?;
+    }
+    Ok(())
+}
+
+fn normalize_dependencies(
+    dependencies: &Map<String, Value>,
+    deps: &mut HashSet<Dependency>,
+) -> Result<(), Error> {
+    for (name, value) in dependencies {
+        let Some(package) = value.as_object() else {
+            return Err(Error::invalid_format());
+        };
+        let version = package
+            .get("version")
+            .and_then(Value::as_str)
+            .ok_or_else(|| Error::invalid_package(name))?;
+        let resolved = package.get("resolved").and_then(Value::as_str);
+        insert_dependency(deps, normalize_single_package(name, version, resolved))?;
+
+        if let Some(nested) = package.get("dependencies") {
+            let Some(nested) = nested.as_object() else {
+                return Err(Error::invalid_format());
+            };
+            normalize_dependencies(nested, deps)?;
+        }
+    }
+    Ok(())
+}
+
+fn normalize_json(value: Value) -> Result<HashSet<Dependency>, Error> {
+    let Some(map) = value.as_object() else {
+        return Err(Error::invalid_format());
+    };
+    let mut deps: HashSet<Dependency> = HashSet::new();
+
+    if let Some(packages) = map.get("packages") {
+        let Some(packages) = packages.as_object() else {
+            return Err(Error::invalid_format());
+        };
+        normalize_packages(packages, &mut deps)?;
+    } else if let Some(dependencies) = map.get("dependencies") {
+        let Some(dependencies) = dependencies.as_object() else {
+            return Err(Error::invalid_format());
+        };
+        normalize_dependencies(dependencies, &mut deps)?;
+    }
+    Ok(deps)
+}
+
+pub fn normalize(value: &str) -> Result<HashSet<Dependency>, Error> {
+    let json: Value = serde_json::from_str(value)?;
+    normalize_json(json)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn test_normalize_v1() {
+        let lockfile = indoc! {r#"
+          {
+            "name": "npm-lock",
+            "version": "1.0.0",
+            "lockfileVersion": 1,
+            "requires": true,
+            "dependencies": {
+              "@fortawesome/pro-solid-svg-icons": {
+                "version": "6.4.0",
+                "resolved": "https://npm.fontawesome.com/@fortawesome/pro-solid-svg-icons/-/6.4.0/pro-solid-svg-icons-6.4.0.tgz",
+                "integrity": "sha512-..."
+              },
+              "cjk-slug": {
+                "version": "git+ssh://git@github.com/daangn/cjk-slug.git#de5d97557a09ad61ae6ac48b1258b67d304660f0",
+                "from": "cjk-slug@git+ssh://git@github.com/daangn/cjk-slug.git",
+                "requires": {
+                  "normalize-cjk": "^0.4.0"
+                }
+              },
+              "local-utils": {
+                "version": "file:../local-utils"
+              },
+              "normalize-cjk": {
+                "version": "0.4.0",
+                "resolved": "https://registry.npmjs.org/normalize-cjk/-/normalize-cjk-0.4.0.tgz",
+                "integrity": "sha512-..."
+              },
+              "semver": {
+                "version": "7.5.1",
+                "resolved": "https://registry.npmjs.org/semver/-/semver-7.5.1.tgz",
+                "integrity": "sha512-...",
+                "requires": {
+                  "lru-cache": "^6.0.0"
+                },
+                "dependencies": {
+                  "lru-cache": {
+                    "version": "6.0.0",
+                    "resolved": "https://registry.npmjs.org/lru-cache/-/lru-cache-6.0.0.tgz",
+                    "integrity": "sha512-..."
+                  }
+                }
+              },
+              "remote-kit": {
+                "version": "https://example.com/remote-kit-1.0.0.tgz",
+                "integrity": "sha512-..."
+              },
+              "string-width-cjs": {
+                "version": "npm:string-width@4.2.3",
+                "resolved": "https://registry.npmjs.org/string-width/-/string-width-4.2.3.tgz",
+                "integrity": "sha512-..."
+              }
+            }
+          }
+        "#};
+
+        let result = normalize(lockfile).unwrap();
+        assert_eq!(
+            result,
+            HashSet::from([
+                Dependency::Npm {
+                    name: "normalize-cjk".into(),
+                    version: "0.4.0".into(),
+                },
+                Dependency::Npm {
+                    name: "semver".into(),
+                    version: "7.5.1".into(),
+                },
+                Dependency::Npm {
+                    name: "lru-cache".into(),
+                    version: "6.0.0".into(),
+                },
+                Dependency::Npm {
+                    name: "string-width".into(),
+                    version: "4.2.3".into(),
+                },
+                Dependency::GitHub {
+                    owner: "daangn".into(),
+                    name: "cjk-slug".into(),
+                    head: Some("de5d97557a09ad61ae6ac48b1258b67d304660f0".into()),
+                },
+            ]),
+        );
+    }
+
+    #[test]
+    fn test_normalize_v3() {
+        let lockfile = indoc! {r#"
+          {
+            "name": "npm-lock",
+            "version": "1.0.0",
+            "lockfileVersion": 3,
+            "requires": true,
+            "packages": {
+              "": {
+                "name": "npm-lock",
+                "version": "1.0.0",
+                "workspaces": ["packages/*"],
+                "dependencies": {
+                  "@types/lodash": "^4.14.195",
+                  "cjk-slug": "github:daangn/cjk-slug",
+                  "private-lib": "git+ssh://git@git.example.com/team/private-lib.git",
+                  "semver": "^7.5.1"
+                }
+              },
+              "node_modules/@types/lodash": {
+                "version": "4.14.195",
+                "resolved": "https://registry.npmjs.org/@types/lodash/-/lodash-4.14.195.tgz",
+                "integrity": "sha512-..."
+              },
+              "node_modules/cjk-slug": {
+                "version": "0.3.1",
+                "resolved": "git+ssh://git@github.com/daangn/cjk-slug.git#de5d97557a09ad61ae6ac48b1258b67d304660f0",
+                "dependencies": {
+                  "normalize-cjk": "^0.4.0"
+                }
+              },
+              "node_modules/local-utils": {
+                "resolved": "packages/local-utils",
+                "link": true
+              },
+              "node_modules/lru-cache": {
+                "version": "6.0.0",
+                "resolved": "https://registry.npmjs.org/lru-cache/-/lru-cache-6.0.0.tgz",
+                "integrity": "sha512-..."
+              },
+              "node_modules/private-lib": {
+                "version": "1.0.0",
+                "resolved": "git+ssh://git@git.example.com/team/private-lib.git#0123456789abcdef0123456789abcdef01234567"
+              },
+              "node_modules/semver": {
+                "version": "7.5.1",
+                "resolved": "https://registry.npmjs.org/semver/-/semver-7.5.1.tgz",
+                "integrity": "sha512-...",
+                "dependencies": {
+                  "lru-cache": "^6.0.0"
+                }
+              },
+              "node_modules/string-width-cjs": {
+                "name": "string-width",
+                "version": "4.2.3",
+                "resolved": "https://registry.npmjs.org/string-width/-/string-width-4.2.3.tgz",
+                "integrity": "sha512-..."
+              },
+              "packages/local-utils": {
+                "version": "0.0.0",
+                "dependencies": {
+                  "lru-cache": "^9.1.2"
+                }
+              },
+              "packages/local-utils/node_modules/lru-cache": {
+                "version": "9.1.2",
+                "resolved": "https://registry.npmjs.org/lru-cache/-/lru-cache-9.1.2.tgz",
+                "integrity": "sha512-..."
+              }
+            }
+          }
+        "#};
+
+        let result = normalize(lockfile).unwrap();
+        assert_eq!(
+            result,
+            HashSet::from([
+                Dependency::Npm {
+                    name: "@types/lodash".into(),
+                    version: "4.14.195".into(),
+                },
+                Dependency::Npm {
+                    name: "semver".into(),
+                    version: "7.5.1".into(),
+                },
+                Dependency::Npm {
+                    name: "lru-cache".into(),
+                    version: "6.0.0".into(),
+                },
+                Dependency::Npm {
+                    name: "lru-cache".into(),
+                    version: "9.1.2".into(),
+                },
+                Dependency::Npm {
+                    name: "string-width".into(),
+                    version: "4.2.3".into(),
+                },
+                Dependency::GitHub {
+                    owner: "daangn".into(),
+                    name: "cjk-slug".into(),
+                    head: Some("de5d97557a09ad61ae6ac48b1258b67d304660f0".into()),
+                },
+                Dependency::Git {
+                    url: "git@git.example.com:team/private-lib.git".into(),
+                    head: Some("0123456789abcdef0123456789abcdef01234567".into()),
+                },
+            ]),
+        );
+    }
+
+    #[test]
+    fn test_registry_tarball() {
+        assert!(is_registry_tarball(
+            "@types/lodash",
+            "4.14.195",
+            "https://registry.npmjs.org/@types/lodash/-/lodash-4.14.195.tgz",
+        ));
+        assert!(!is_registry_tarball(
+            "@fortawesome/pro-solid-svg-icons",
+            "6.4.0",
+            "https://npm.fontawesome.com/@fortawesome/pro-solid-svg-icons/-/6.4.0/pro-solid-svg-icons-6.4.0.tgz",
+        ));
+        assert!(!is_registry_tarball("local", "1.0.0", "file:../local"));
+    }
+}
@@ -0,0 +1,183 @@
+use std::collections::HashSet;
+
+use crate::dependency::Dependency;
+
+use serde_json::{Map, Value};
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum Error {
+    #[error("Couldn't parse the lockfile.\n{message}")]
+    InvalidLockfileFormat { message: String },
+
+    #[error("Unsupported resolution: {resolution}")]
+    UnsupportedResolution { resolution: String },
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(_error: serde_json::Error) -> Self {
+        Self::invalid_json()
+    }
+}
+
+impl Error {
+    fn invalid_json() -> Self {
+        Self::InvalidLockfileFormat {
+            message: "Not a valid JSON".into(),
+        }
+    }
+
+    fn invalid_format() -> Self {
+        Self::InvalidLockfileFormat {
+            message: "Malformed lockfile".into(),
+        }
+    }
+
+    fn invalid_package(id: &str) -> Self {
+        Self::InvalidLockfileFormat {
+            message: format!("Some package has unsupported form: {id}"),
+        }
+    }
+}
+
+// Lockfile layout:
+//
+// `dependencies` maps each target framework (e.g. `net6.0`, `net6.0/win-x64`)
+// to its packages `{ type, requested, resolved, contentHash, dependencies }`.
+//
+// "type" is one of `Direct`, `Transitive`, `CentralTransitive` and `Project`.
+// `Project` is a reference to another project in the same solution.
+//
+// The same package is usually resolved for every target framework,
+// so entries are merged across them.
+
+fn normalize_single_package(id: &str, package: &Map<String, Value>) -> Result<Dependency, Error> {
+    let package_type = package
+        .get("type")
+        .and_then(Value::as_str)
+        .ok_or_else(|| Error::invalid_package(id))?;
+    match package_type {
+        "Direct" | "Transitive" | "CentralTransitive" => {
+            let version = package
+                .get("resolved")
+                .and_then(Value::as_str)
+                .ok_or_else(|| Error::invalid_package(id))?;
+            Ok(Dependency::NuGet {
+                id: id.into(),
+                version: version.into(),
+            })
+        }
+        // project reference is not a package
+        "Project" => Err(Error::UnsupportedResolution {
+            resolution: id.into(),
+        }),
+        _ => Err(Error::invalid_package(id)),
+    }
+}
+
+fn normalize_json(value: Value) -> Result<HashSet<Dependency>, Error> {
+    let mut deps: HashSet<Dependency> = HashSet::new();
+
+    let Some(frameworks) = value.get("dependencies").and_then(Value::as_object) else {
+        return Err(Error::invalid_format());
+    };
+    for packages in frameworks.values() {
+        let Some(packages) = packages.as_object() else {
+            return Err(Error::invalid_format());
+        };
+        for (id, package) in packages {
+            let Some(package) = package.as_object() else {
+                return Err(Error::invalid_format());
+            };
+            match normalize_single_package(id, package) {
+                Ok(dependency) => {
+                    deps.insert(dependency);
+                }
+                Err(Error::UnsupportedResolution { .. }) => {
+                    // noop
+                }
+                Err(error) => {
+                    return Err(error);
+                }
+            }
+        }
+    }
+    Ok(deps)
+}
+
+pub fn normalize(value: &str) -> Result<HashSet<Dependency>, Error> {
+    let json: Value = serde_json::from_str(value)?;
+    normalize_json(json)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let lockfile = indoc! {r#"
+          {
+            "version": 1,
+            "dependencies": {
+              "net6.0": {
+                "Karrot.Sdk.Core": {
+                  "type": "Project",
+                  "dependencies": {
+                    "Newtonsoft.Json": "[13.0.3, )"
+                  }
+                },
+                "Microsoft.Extensions.Logging.Abstractions": {
+                  "type": "CentralTransitive",
+                  "requested": "[7.0.0, )",
+                  "resolved": "7.0.0",
+                  "contentHash": "..."
+                },
+                "Newtonsoft.Json": {
+                  "type": "Direct",
+                  "requested": "[13.0.3, )",
+                  "resolved": "13.0.3",
+                  "contentHash": "..."
+                }
+              },
+              "netstandard2.0": {
+                "Newtonsoft.Json": {
+                  "type": "Direct",
+                  "requested": "[13.0.3, )",
+                  "resolved": "13.0.3",
+                  "contentHash": "..."
+                },
+                "System.Memory": {
+                  "type": "Transitive",
+                  "resolved": "4.5.5",
+                  "contentHash": "...",
+                  "dependencies": {
+                    "System.Buffers": "4.5.1"
+                  }
+                }
+              }
+            }
+          }
+        "#};
+
+        let result = normalize(lockfile).unwrap();
+        assert_eq!(
+            result,
+            HashSet::from([
+                Dependency::NuGet {
+                    id: "Microsoft.Extensions.Logging.Abstractions".into(),
+                    version: "7.0.0".into(),
+                },
+                Dependency::NuGet {
+                    id: "Newtonsoft.Json".into(),
+                    version: "13.0.3".into(),
+                },
+                Dependency::NuGet {
+                    id: "System.Memory".into(),
+                    version: "4.5.5".into(),
+                },
+            ]),
+        );
+    }
+}
@@ -0,0 +1,317 @@
+use std::collections::HashSet;
+
+use crate::dependency::Dependency;
+
+use super::poetry::{normalize_github_archive, PYPI_INDEXES};
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum Error {
+    #[error("Couldn't parse the lockfile.\n{message}")]
+    InvalidLockfileFormat { message: String },
+
+    #[error("Unsupported resolution: {resolution}")]
+    UnsupportedResolution { resolution: String },
+}
+
+impl Error {
+    fn invalid_requirement(requirement: &str) -> Self {
+        Self::InvalidLockfileFormat {
+            message: format!("Some requirement has unsupported form: {requirement}"),
+        }
+    }
+}
+
+// Requirement line parsing rule (a fully pinned `requirements.txt`, e.g. by `pip-compile`):
+//
+// "line" follows form of `(-e )<requirement>( ; <markers>)( --hash=<hash>)*( # <comment>)`
+// and may continue to the next line with a trailing `\`.
+//
+// "requirement" is one of
+//
+// - `<name>([<extras>])==<version>`
+// - `<name>([<extras>]) @ <url>` (PEP 508 direct reference)
+// - `<vcs>+<url>(@<revision>)(#egg=<name>)` (only with `-e` in older pip)
+//
+// `--index-url` (`-i`) replaces the primary index for the whole file, and the last one wins.
+// `--extra-index-url` only adds a fallback, so pins are still resolved from the primary index.
+// Other options (e.g. `-r`, `-c`) don't pin anything.
+
+fn logical_lines(value: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut current = String::new();
+
+    for line in value.lines() {
+        // comments start at the beginning of a line or after a whitespace
+        let line = match line.find(" #") {
+            Some(index) => &line[..index],
+            None if line.trim_start().starts_with('#') => "",
+            None => line,
+        };
+        match line.trim_end().strip_suffix('\\') {
+            Some(line) => {
+                current.push_str(line.trim());
+                current.push(' ');
+            }
+            None => {
+                current.push_str(line.trim());
+                let line = current.trim().to_owned();
+                if !line.is_empty() {
+                    lines.push(line);
+                }
+                current.clear();
+            }
+        }
+    }
+    lines
+}
+
+fn normalize_vcs_url(url: &str) -> Result<Dependency, Error> {
+    let (url, _fragment) = url.split_once('#').unwrap_or((url, ""));
+    let Some(scheme_end) = url.find("://") else {
+        return Err(Error::invalid_requirement(url));
+    };
+    // revision is after the path (e.g. `git+ssh://git@github.com/owner/name.git@<revision>`)
+    let path_start = url[scheme_end + 3..]
+        .find('/')
+        .map_or(url.len(), |index| scheme_end + 3 + index);
+    let (url, head) = match url[path_start..].rfind('@') {
+        Some(index) => (
+            &url[..path_start + index],
+            Some(url[path_start + index + 1..].to_owned()),
+        ),
+        None => (url, None),
+    };
+    if !url.starts_with("git+") {
+        // other VCS like `hg+` and `svn+` are not supported
+        return Err(Error::UnsupportedResolution {
+            resolution: url.into(),
+        });
+    }
+    Ok(Dependency::Git {
+        url: url.into(),
+        head,
+    }
+    .canonicalize())
+}
+
+/// URL of the primary index, if the line is `--index-url` option.
+fn parse_index_url(line: &str) -> Option<&str> {
+    let (option, value) = line.split_once([' ', '='])?;
+    if option != "--index-url" && option != "-i" {
+        return None;
+    }
+    Some(value.trim())
+}
+
+fn normalize_requirement(line: &str, index_url: &str) -> Result<Dependency, Error> {
+    let line = line
+        .strip_prefix("-e ")
+        .or_else(|| line.strip_prefix("--editable "))
+        .unwrap_or(line)
+        .trim();
+    let (requirement, _markers) = line.split_once(';').unwrap_or((line, ""));
+    let requirement: String = requirement
+        .split_whitespace()
+        .take_while(|token| !token.starts_with("--"))
+        .collect::<Vec<&str>>()
+        .join(" ");
+
+    // `@` of a direct reference comes before the URL (`<name>@<url>`, spaces are optional)
+    let scheme = requirement.find("://");
+    let direct = requirement
+        .find('@')
+        .filter(|index| scheme.is_none_or(|scheme| *index < scheme));
+    if let Some(index) = direct {
+        let url = requirement[index + 1..].trim();
+        if url
+            .split_once("://")
+            .is_some_and(|(scheme, _)| scheme.contains('+'))
+        {
+            return normalize_vcs_url(url);
+        }
+        // arbitrary archive and local path are not supported
+        return normalize_github_archive(url).ok_or_else(|| Error::UnsupportedResolution {
+            resolution: url.into(),
+        });
+    }
+    if scheme.is_some() {
+        return normalize_vcs_url(&requirement);
+    }
+    // local path is not supported
+    if requirement.starts_with('.') || requirement.starts_with('/') {
+        return Err(Error::UnsupportedResolution {
+            resolution: requirement,
+        });
+    }
+
+    let Some((name, version)) = requirement.split_once("==") else {
+        // not pinned
+        return Err(Error::UnsupportedResolution {
+            resolution: requirement,
+        });
+    };
+    // private index is not supported
+    if !PYPI_INDEXES.contains(&index_url) {
+        return Err(Error::UnsupportedResolution {
+            resolution: index_url.into(),
+        });
+    }
+    let name = name.split('[').next().unwrap().trim();
+    let version = version.strip_prefix('=').unwrap_or(version).trim();
+    if name.is_empty() || version.is_empty() {
+        return Err(Error::invalid_requirement(&requirement));
+    }
+    Ok(Dependency::PyPI {
+        name: name.into(),
+        version: version.into(),
+    }
+    .canonicalize())
+}
+
+pub fn normalize(value: &str) -> Result<HashSet<Dependency>, Error> {
+    let mut deps: HashSet<Dependency> = HashSet::new();
+
+    let lines = logical_lines(value);
+    let index_url = lines
+        .iter()
+        .rev()
+        .find_map(|line| parse_index_url(line))
+        .unwrap_or(PYPI_INDEXES[0])
+        .to_owned();
+    for line in lines {
+        let is_editable = line.starts_with("-e ") || line.starts_with("--editable ");
+        if line.starts_with('-') && !is_editable {
+            continue;
+        }
+        match normalize_requirement(&line, &index_url) {
+            Ok(dependency) => {
+                deps.insert(dependency);
+            }
+            Err(Error::UnsupportedResolution { .. }) => {
+                // noop
+            }
+            Err(error) => {
+                return Err(error);
+            }
+        }
+    }
+    Ok(deps)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let lockfile = indoc! {r#"
+          #
+          # This file is autogenerated by pip-compile with Python 3.11
+          # by the following command:
+          #
+          #    pip-compile --generate-hashes requirements.in
+          #
+          --index-url https://pypi.org/simple
+          --extra-index-url https://pypi.example.com/simple
+
+          -e git+https://github.com/daangn/karrot-ml.git@de5d97557a09ad61ae6ac48b1258b67d304660f0#egg=karrot-ml
+              # via -r requirements.in
+          -e ./local-utils
+              # via -r requirements.in
+          certifi==2023.5.7 \
+              --hash=sha256:... \
+              --hash=sha256:...
+              # via requests
+          internal-client @ git+ssh://git@git.example.com/team/internal-client.git@v1.0.0
+              # via -r requirements.in
+          karrot-vision@git+https://github.com/daangn/karrot-vision.git@0123456789abcdef0123456789abcdef01234567
+              # via -r requirements.in
+          requests[socks]==2.31.0 \
+              --hash=sha256:...
+              # via -r requirements.in
+          typing_extensions==4.7.1 ; python_version < "3.12" \
+              --hash=sha256:...
+              # via -r requirements.in
+          urllib3>=2.0
+        "#};
+
+        let result = normalize(lockfile).unwrap();
+        assert_eq!(
+            result,
+            HashSet::from([
+                Dependency::PyPI {
+                    name: "certifi".into(),
+                    version: "2023.5.7".into(),
+                },
+                Dependency::PyPI {
+                    name: "requests".into(),
+                    version: "2.31.0".into(),
+                },
+                Dependency::PyPI {
+                    name: "typing-extensions".into(),
+                    version: "4.7.1".into(),
+                },
+                Dependency::GitHub {
+                    owner: "daangn".into(),
+                    name: "karrot-ml".into(),
+                    head: Some("de5d97557a09ad61ae6ac48b1258b67d304660f0".into()),
+                },
+                Dependency::GitHub {
+                    owner: "daangn".into(),
+                    name: "karrot-vision".into(),
+                    head: Some("0123456789abcdef0123456789abcdef01234567".into()),
+                },
+                Dependency::Git {
+                    url: "git@git.example.com:team/internal-client.git".into(),
+                    head: Some("v1.0.0".into()),
+                },
+            ]),
+        );
+    }
+
+    #[test]
+    fn test_normalize_private_index() {
+        let lockfile = indoc! {r#"
+          -i https://pypi.example.com/simple
+
+          certifi==2023.5.7
+              # via requests
+          -e git+https://github.com/daangn/karrot-ml.git@de5d97557a09ad61ae6ac48b1258b67d304660f0#egg=karrot-ml
+              # via -r requirements.in
+          karrot-auth==1.2.0
+              # via -r requirements.in
+        "#};
+
+        let result = normalize(lockfile).unwrap();
+        assert_eq!(
+            result,
+            HashSet::from([Dependency::GitHub {
+                owner: "daangn".into(),
+                name: "karrot-ml".into(),
+                head: Some("de5d97557a09ad61ae6ac48b1258b67d304660f0".into()),
+            }]),
+        );
+    }
+
+    #[test]
+    fn test_logical_lines() {
+        let lockfile = indoc! {r#"
+          # comment
+          certifi==2023.5.7 \
+              --hash=sha256:aaa \
+              --hash=sha256:bbb
+              # via requests
+          -e git+https://github.com/owner/name.git@v1#egg=name
+        "#};
+
+        assert_eq!(
+            logical_lines(lockfile),
+            vec![
+                "certifi==2023.5.7 --hash=sha256:aaa --hash=sha256:bbb".to_owned(),
+                "-e git+https://github.com/owner/name.git@v1#egg=name".to_owned(),
+            ],
+        );
+    }
+}
@@ -0,0 +1,301 @@
+use std::collections::HashSet;
+
+use crate::dependency::Dependency;
+
+use serde_json::{Map, Value};
+
+use super::poetry::{normalize_github_archive, PYPI_INDEXES};
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum Error {
+    #[error("Couldn't parse the lockfile.\n{message}")]
+    InvalidLockfileFormat { message: String },
+
+    #[error("Unsupported resolution: {resolution}")]
+    UnsupportedResolution { resolution: String },
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(_error: serde_json::Error) -> Self {
+        Self::invalid_json()
+    }
+}
+
+impl Error {
+    fn invalid_json() -> Self {
+        Self::InvalidLockfileFormat {
+            message: "Not a valid JSON".into(),
+        }
+    }
+
+    fn invalid_format() -> Self {
+        Self::InvalidLockfileFormat {
+            message: "Malformed lockfile".into(),
+        }
+    }
+
+    fn invalid_package(name: &str) -> Self {
+        Self::InvalidLockfileFormat {
+            message: format!("Some package has unsupported form: {name}"),
+        }
+    }
+}
+
+// Lockfile layout:
+//
+// `default` and `develop` map package names to one of
+//
+// - `{ version: "==<version>", index: <source name> }`
+// - `{ git: <url>, ref: <commit> }`
+// - `{ file: <url> }`
+// - `{ path: <path>, editable: true }`
+//
+// Source names of `index` are defined in `_meta.sources`.
+// A package without `index` comes from the first source.
+
+/// Names and URLs of the sources, in the order of `_meta.sources`.
+fn parse_sources(value: &Value) -> Result<Vec<(String, String)>, Error> {
+    let Some(sources) = value
+        .get("_meta")
+        .and_then(|meta| meta.get("sources"))
+        .and_then(Value::as_array)
+    else {
+        return Ok(Vec::new());
+    };
+    sources
+        .iter()
+        .map(|source| {
+            let name = source.get("name").and_then(Value::as_str);
+            let url = source.get("url").and_then(Value::as_str);
+            match (name, url) {
+                (Some(name), Some(url)) => Ok((name.to_owned(), url.to_owned())),
+                _ => Err(Error::invalid_format()),
+            }
+        })
+        .collect()
+}
+
+fn normalize_single_package(
+    sources: &[(String, String)],
+    name: &str,
+    package: &Map<String, Value>,
+) -> Result<Dependency, Error> {
+    if let Some(url) = package.get("git").and_then(Value::as_str) {
+        let head = package.get("ref").and_then(Value::as_str);
+        return Ok(Dependency::Git {
+            url: url.into(),
+            head: head.map(String::from),
+        }
+        .canonicalize());
+    }
+    if let Some(url) = package.get("file").and_then(Value::as_str) {
+        // arbitrary archive is not supported
+        return normalize_github_archive(url).ok_or_else(|| Error::UnsupportedResolution {
+            resolution: url.into(),
+        });
+    }
+    // local path is not supported
+    if let Some(path) = package.get("path").and_then(Value::as_str) {
+        return Err(Error::UnsupportedResolution {
+            resolution: path.into(),
+        });
+    }
+
+    let Some(version) = package
+        .get("version")
+        .and_then(Value::as_str)
+        .and_then(|version| version.strip_prefix("=="))
+    else {
+        return Err(Error::invalid_package(name));
+    };
+    let version = version.strip_prefix('=').unwrap_or(version);
+    let source = match package.get("index").and_then(Value::as_str) {
+        Some(index) => sources.iter().find(|(name, _)| name == index),
+        None => sources.first(),
+    };
+    match source {
+        Some((_, url)) if PYPI_INDEXES.contains(&url.as_str()) => {}
+        // private index is not supported
+        Some((_, url)) => {
+            return Err(Error::UnsupportedResolution {
+                resolution: url.into(),
+            })
+        }
+        // Pipfile without any source uses PyPI
+        None if sources.is_empty() => {}
+        None => return Err(Error::invalid_package(name)),
+    }
+    Ok(Dependency::PyPI {
+        name: name.into(),
+        version: version.into(),
+    }
+    .canonicalize())
+}
+
+fn normalize_json(value: Value) -> Result<HashSet<Dependency>, Error> {
+    let sources = parse_sources(&value)?;
+    let mut deps: HashSet<Dependency> = HashSet::new();
+
+    for section in ["default", "develop"] {
+        let Some(packages) = value.get(section) else {
+            continue;
+        };
+        let Some(packages) = packages.as_object() else {
+            return Err(Error::invalid_format());
+        };
+        for (name, package) in packages {
+            let Some(package) = package.as_object() else {
+                return Err(Error::invalid_format());
+            };
+            match normalize_single_package(&sources, name, package) {
+                Ok(dependency) => {
+                    deps.insert(dependency);
+                }
+                Err(Error::UnsupportedResolution { .. }) => {
+                    // noop
+                }
+                Err(error) => {
+                    return Err(error);
+                }
+            }
+        }
+    }
+    Ok(deps)
+}
+
+pub fn normalize(value: &str) -> Result<HashSet<Dependency>, Error> {
+    let json: Value = serde_json::from_str(value)?;
+    normalize_json(json)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let lockfile = indoc! {r#"
+          {
+            "_meta": {
+              "hash": {
+                "sha256": "..."
+              },
+              "pipfile-spec": 6,
+              "requires": {
+                "python_version": "3.11"
+              },
+              "sources": [
+                {
+                  "name": "pypi",
+                  "url": "https://pypi.org/simple",
+                  "verify_ssl": true
+                },
+                {
+                  "name": "private",
+                  "url": "https://pypi.example.com/simple",
+                  "verify_ssl": true
+                }
+              ]
+            },
+            "default": {
+              "karrot-ml": {
+                "git": "https://github.com/daangn/karrot-ml.git",
+                "ref": "de5d97557a09ad61ae6ac48b1258b67d304660f0"
+              },
+              "private-lib": {
+                "hashes": [],
+                "index": "private",
+                "version": "==1.0.0"
+              },
+              "requests": {
+                "hashes": [
+                  "sha256:..."
+                ],
+                "index": "pypi",
+                "markers": "python_version >= '3.7'",
+                "version": "==2.31.0"
+              },
+              "typing_extensions": {
+                "hashes": [],
+                "version": "==4.7.1"
+              }
+            },
+            "develop": {
+              "local-utils": {
+                "editable": true,
+                "path": "./local-utils"
+              },
+              "pytest": {
+                "hashes": [],
+                "index": "pypi",
+                "version": "==7.4.0"
+              }
+            }
+          }
+        "#};
+
+        let result = normalize(lockfile).unwrap();
+        assert_eq!(
+            result,
+            HashSet::from([
+                Dependency::PyPI {
+                    name: "requests".into(),
+                    version: "2.31.0".into(),
+                },
+                Dependency::PyPI {
+                    name: "typing-extensions".into(),
+                    version: "4.7.1".into(),
+                },
+                Dependency::PyPI {
+                    name: "pytest".into(),
+                    version: "7.4.0".into(),
+                },
+                Dependency::GitHub {
+                    owner: "daangn".into(),
+                    name: "karrot-ml".into(),
+                    head: Some("de5d97557a09ad61ae6ac48b1258b67d304660f0".into()),
+                },
+            ]),
+        );
+    }
+    #[test]
+    fn test_normalize_private_source() {
+        let lockfile = indoc! {r#"
+          {
+            "_meta": {
+              "pipfile-spec": 6,
+              "sources": [
+                {
+                  "name": "private",
+                  "url": "https://pypi.example.com/simple",
+                  "verify_ssl": true
+                }
+              ]
+            },
+            "default": {
+              "karrot-auth": {
+                "hashes": [],
+                "version": "==1.2.0"
+              },
+              "karrot-ml": {
+                "git": "https://github.com/daangn/karrot-ml.git",
+                "ref": "de5d97557a09ad61ae6ac48b1258b67d304660f0"
+              }
+            },
+            "develop": {}
+          }
+        "#};
+
+        let result = normalize(lockfile).unwrap();
+        assert_eq!(
+            result,
+            HashSet::from([Dependency::GitHub {
+                owner: "daangn".into(),
+                name: "karrot-ml".into(),
+                head: Some("de5d97557a09ad61ae6ac48b1258b67d304660f0".into()),
+            }]),
+        );
+    }
+}
@@ -0,0 +1,448 @@
+use std::collections::HashSet;
+
+use crate::dependency::Dependency;
+
+use serde_yaml::{Mapping, Value};
+
+use super::npm::{is_registry_tarball, normalize_codeload_tarball};
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum Error {
+    #[error("Couldn't parse the lockfile.\n{message}")]
+    InvalidLockfileFormat { message: String },
+
+    #[error("Unsupported resolution: {resolution}")]
+    UnsupportedResolution { resolution: String },
+}
+
+impl From<serde_yaml::Error> for Error {
+    fn from(_error: serde_yaml::Error) -> Self {
+        Self::invalid_yaml()
+    }
+}
+
+impl Error {
+    fn invalid_yaml() -> Self {
+        Self::InvalidLockfileFormat {
+            message: "Not a valid YAML".into(),
+        }
+    }
+
+    fn invalid_format() -> Self {
+        Self::InvalidLockfileFormat {
+            message: "Malformed lockfile".into(),
+        }
+    }
+
+    fn invalid_package_key(key: &str) -> Self {
+        Self::InvalidLockfileFormat {
+            message: format!("Some package has unsupported key: {key}"),
+        }
+    }
+}
+
+// Package key parsing rule:
+//
+// lockfile v5: `/<ident>/<version>(_<peers>)`
+//    (e.g. `/react-dom/18.2.0_react@18.2.0`, peers use `+` instead of `/`)
+// lockfile v6: `/<ident>@<version>(<peers>)`
+//    (e.g. `/react-dom@18.2.0(react@18.2.0)`)
+// lockfile v9: `<ident>@<version>`, peers only appear in `snapshots`
+//
+// Non-registry packages (git, tarball, local directory) have other forms of key,
+// but they always come with explicit `name` and `version` fields.
+//
+// `importers` lists the workspace projects and their direct dependencies.
+// They point to either a package in `packages` or another project (`link:`),
+// so every resolved package can be found in the `packages` section.
+
+fn parse_lockfile_version(map: &Mapping) -> Result<u32, Error> {
+    let version = match map.get("lockfileVersion") {
+        Some(Value::String(version)) => version.to_owned(),
+        Some(Value::Number(version)) => version.to_string(),
+        _ => return Err(Error::invalid_format()),
+    };
+    let major = version.split('.').next().unwrap();
+    major.parse().map_err(|_| Error::invalid_format())
+}
+
+fn parse_package_key(lockfile_version: u32, key: &str) -> Result<(String, String), Error> {
+    let substr = key.strip_prefix('/').unwrap_or(key);
+    if lockfile_version < 6 {
+        let Some((ident, version)) = substr.rsplit_once('/') else {
+            return Err(Error::invalid_package_key(key));
+        };
+        let (version, _peers) = version.split_once('_').unwrap_or((version, ""));
+        return Ok((ident.into(), version.into()));
+    }
+
+    let index = if substr.starts_with('@') {
+        substr.match_indices('@').nth(1).map(|(index, _)| index)
+    } else {
+        substr.find('@')
+    };
+    let Some(index) = index else {
+        return Err(Error::invalid_package_key(key));
+    };
+    let (ident, version) = (&substr[..index], &substr[index + 1..]);
+    let (version, _peers) = version.split_once('(').unwrap_or((version, ""));
+    Ok((ident.into(), version.into()))
+}
+
+fn normalize_single_package(
+    lockfile_version: u32,
+    key: &str,
+    package: &Mapping,
+) -> Result<Dependency, Error> {
+    let resolution = package
+        .get("resolution")
+        .and_then(Value::as_mapping)
+        .ok_or_else(Error::invalid_format)?;
+
+    if resolution.get("type").and_then(Value::as_str) == Some("git") {
+        let repo = resolution.get("repo").and_then(Value::as_str);
+        let commit = resolution.get("commit").and_then(Value::as_str);
+        let (Some(repo), Some(commit)) = (repo, commit) else {
+            return Err(Error::invalid_format());
+        };
+        return Ok(Dependency::Git {
+            url: repo.into(),
+            head: Some(commit.into()),
+        }
+        .canonicalize());
+    }
+    // local packages are not supported
+    if resolution.contains_key("directory") {
+        return Err(Error::UnsupportedResolution {
+            resolution: key.into(),
+        });
+    }
+
+    let tarball = resolution.get("tarball").and_then(Value::as_str);
+    if let Some(dependency) = tarball.and_then(normalize_codeload_tarball) {
+        return Ok(dependency);
+    }
+
+    let (name, version) = match (
+        package.get("name").and_then(Value::as_str),
+        package.get("version").and_then(Value::as_str),
+    ) {
+        (Some(name), Some(version)) => (name.to_owned(), version.to_owned()),
+        _ => parse_package_key(lockfile_version, key)?,
+    };
+    match tarball {
+        // private/custom registry and local tarball are not supported
+        Some(tarball) if !is_registry_tarball(&name, &version, tarball) => {
+            Err(Error::UnsupportedResolution {
+                resolution: tarball.into(),
+            })
+        }
+        _ => Ok(Dependency::Npm { name, version }),
+    }
+}
+
+fn normalize_yaml(value: Value) -> Result<HashSet<Dependency>, Error> {
+    let Some(map) = value.as_mapping() else {
+        return Err(Error::invalid_format());
+    };
+    let lockfile_version = parse_lockfile_version(map)?;
+    let mut deps: HashSet<Dependency> = HashSet::new();
+
+    // A project without any dependencies doesn't have the `packages` section
+    let Some(packages) = map.get("packages") else {
+        return Ok(deps);
+    };
+    let Some(packages) = packages.as_mapping() else {
+        return Err(Error::invalid_format());
+    };
+    for (key, value) in packages {
+        let (Some(key), Some(package)) = (key.as_str(), value.as_mapping()) else {
+            return Err(Error::invalid_format());
+        };
+        match normalize_single_package(lockfile_version, key, package) {
+            Ok(dependency) => {
+                deps.insert(dependency);
+            }
+            Err(Error::UnsupportedResolution { .. }) => {
+                // noop
+            }
+            Err(error) => {
+                return Err(error);
+            }
+        }
+    }
+    Ok(deps)
+}
+
+pub fn normalize(value: &str) -> Result<HashSet<Dependency>, Error> {
+    let yaml: Value = serde_yaml::from_str(value)?;
+    normalize_yaml(yaml)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    fn expected() -> HashSet<Dependency> {
+        HashSet::from([
+            Dependency::Npm {
+                name: "@types/react".into(),
+                version: "18.2.14".into(),
+            },
+            Dependency::Npm {
+                name: "react".into(),
+                version: "18.2.0".into(),
+            },
+            Dependency::Npm {
+                name: "react-dom".into(),
+                version: "18.2.0".into(),
+            },
+            Dependency::Npm {
+                name: "normalize-cjk".into(),
+                version: "0.4.0".into(),
+            },
+            Dependency::GitHub {
+                owner: "daangn".into(),
+                name: "cjk-slug".into(),
+                head: Some("de5d97557a09ad61ae6ac48b1258b67d304660f0".into()),
+            },
+            Dependency::Git {
+                url: "https://git.example.com/team/private-lib.git".into(),
+                head: Some("0123456789abcdef0123456789abcdef01234567".into()),
+            },
+        ])
+    }
+
+    #[test]
+    fn test_normalize_v5() {
+        let lockfile = indoc! {r#"
+          lockfileVersion: 5.4
+
+          importers:
+
+            .:
+              specifiers:
+                cjk-slug: github:daangn/cjk-slug
+                local-utils: workspace:*
+                react-dom: ^18.2.0
+              dependencies:
+                cjk-slug: github.com/daangn/cjk-slug/de5d97557a09ad61ae6ac48b1258b67d304660f0
+                local-utils: link:packages/local-utils
+                react-dom: 18.2.0_react@18.2.0
+
+            packages/local-utils:
+              specifiers:
+                '@types/react': ^18.2.14
+                private-lib: git+https://git.example.com/team/private-lib.git
+              dependencies:
+                '@types/react': 18.2.14
+                private-lib: git.example.com/team/private-lib/0123456789abcdef0123456789abcdef01234567
+
+          packages:
+
+            /@types/react/18.2.14:
+              resolution: {integrity: sha512-...}
+              dev: false
+
+            /normalize-cjk/0.4.0:
+              resolution: {integrity: sha512-...}
+              dev: false
+
+            /react-dom/18.2.0_react@18.2.0:
+              resolution: {integrity: sha512-...}
+              peerDependencies:
+                react: ^18.2.0
+              dependencies:
+                react: 18.2.0
+              dev: false
+
+            /react/18.2.0:
+              resolution: {integrity: sha512-...}
+              dev: false
+
+            git.example.com/team/private-lib/0123456789abcdef0123456789abcdef01234567:
+              resolution: {commit: 0123456789abcdef0123456789abcdef01234567, repo: https://git.example.com/team/private-lib.git, type: git}
+              name: private-lib
+              version: 1.0.0
+              dev: false
+
+            github.com/daangn/cjk-slug/de5d97557a09ad61ae6ac48b1258b67d304660f0:
+              resolution: {tarball: https://codeload.github.com/daangn/cjk-slug/tar.gz/de5d97557a09ad61ae6ac48b1258b67d304660f0}
+              name: cjk-slug
+              version: 0.3.1
+              dependencies:
+                normalize-cjk: 0.4.0
+              dev: false
+        "#};
+
+        let result = normalize(lockfile).unwrap();
+        assert_eq!(result, expected());
+    }
+
+    #[test]
+    fn test_normalize_v6() {
+        let lockfile = indoc! {r#"
+          lockfileVersion: '6.0'
+
+          importers:
+
+            .:
+              dependencies:
+                cjk-slug:
+                  specifier: github:daangn/cjk-slug
+                  version: github.com/daangn/cjk-slug/de5d97557a09ad61ae6ac48b1258b67d304660f0
+                react-dom:
+                  specifier: ^18.2.0
+                  version: 18.2.0(react@18.2.0)
+
+            packages/local-utils:
+              dependencies:
+                '@types/react':
+                  specifier: ^18.2.14
+                  version: 18.2.14
+                private-lib:
+                  specifier: git+https://git.example.com/team/private-lib.git
+                  version: git.example.com/team/private-lib/0123456789abcdef0123456789abcdef01234567
+
+          packages:
+
+            /@fortawesome/pro-solid-svg-icons@6.4.0:
+              resolution: {integrity: sha512-..., tarball: https://npm.fontawesome.com/@fortawesome/pro-solid-svg-icons/-/6.4.0/pro-solid-svg-icons-6.4.0.tgz}
+              dev: false
+
+            /@types/react@18.2.14:
+              resolution: {integrity: sha512-...}
+              dev: false
+
+            /normalize-cjk@0.4.0:
+              resolution: {integrity: sha512-...}
+              dev: false
+
+            /react-dom@18.2.0(react@18.2.0):
+              resolution: {integrity: sha512-...}
+              peerDependencies:
+                react: ^18.2.0
+              dependencies:
+                react: 18.2.0
+              dev: false
+
+            /react@18.2.0:
+              resolution: {integrity: sha512-...}
+              dev: false
+
+            git.example.com/team/private-lib/0123456789abcdef0123456789abcdef01234567:
+              resolution: {commit: 0123456789abcdef0123456789abcdef01234567, repo: https://git.example.com/team/private-lib.git, type: git}
+              name: private-lib
+              version: 1.0.0
+              dev: false
+
+            github.com/daangn/cjk-slug/de5d97557a09ad61ae6ac48b1258b67d304660f0:
+              resolution: {tarball: https://codeload.github.com/daangn/cjk-slug/tar.gz/de5d97557a09ad61ae6ac48b1258b67d304660f0}
+              name: cjk-slug
+              version: 0.3.1
+              dependencies:
+                normalize-cjk: 0.4.0
+              dev: false
+        "#};
+
+        let result = normalize(lockfile).unwrap();
+        assert_eq!(result, expected());
+    }
+
+    #[test]
+    fn test_normalize_v9() {
+        let lockfile = indoc! {r#"
+          lockfileVersion: '9.0'
+
+          settings:
+            autoInstallPeers: true
+            excludeLinksFromLockfile: false
+
+          importers:
+
+            .:
+              dependencies:
+                cjk-slug:
+                  specifier: github:daangn/cjk-slug
+                  version: https://codeload.github.com/daangn/cjk-slug/tar.gz/de5d97557a09ad61ae6ac48b1258b67d304660f0
+                local-utils:
+                  specifier: workspace:*
+                  version: link:packages/local-utils
+                react-dom:
+                  specifier: ^18.2.0
+                  version: 18.2.0(react@18.2.0)
+
+            packages/local-utils:
+              dependencies:
+                '@types/react':
+                  specifier: ^18.2.14
+                  version: 18.2.14
+                private-lib:
+                  specifier: git+https://git.example.com/team/private-lib.git
+                  version: git+https://git.example.com/team/private-lib.git#0123456789abcdef0123456789abcdef01234567
+
+          packages:
+
+            '@types/react@18.2.14':
+              resolution: {integrity: sha512-...}
+
+            cjk-slug@https://codeload.github.com/daangn/cjk-slug/tar.gz/de5d97557a09ad61ae6ac48b1258b67d304660f0:
+              resolution: {tarball: https://codeload.github.com/daangn/cjk-slug/tar.gz/de5d97557a09ad61ae6ac48b1258b67d304660f0}
+              version: 0.3.1
+
+            normalize-cjk@0.4.0:
+              resolution: {integrity: sha512-...}
+
+            private-lib@git+https://git.example.com/team/private-lib.git#0123456789abcdef0123456789abcdef01234567:
+              resolution: {commit: 0123456789abcdef0123456789abcdef01234567, repo: https://git.example.com/team/private-lib.git, type: git}
+              version: 1.0.0
+
+            react-dom@18.2.0:
+              resolution: {integrity: sha512-...}
+              peerDependencies:
+                react: ^18.2.0
+
+            react@18.2.0:
+              resolution: {integrity: sha512-...}
+
+          snapshots:
+
+            '@types/react@18.2.14': {}
+
+            cjk-slug@https://codeload.github.com/daangn/cjk-slug/tar.gz/de5d97557a09ad61ae6ac48b1258b67d304660f0:
+              dependencies:
+                normalize-cjk: 0.4.0
+
+            normalize-cjk@0.4.0: {}
+
+            private-lib@git+https://git.example.com/team/private-lib.git#0123456789abcdef0123456789abcdef01234567: {}
+
+            react-dom@18.2.0(react@18.2.0):
+              dependencies:
+                react: 18.2.0
+
+            react@18.2.0: {}
+        "#};
+
+        let result = normalize(lockfile).unwrap();
+        assert_eq!(result, expected());
+    }
+
+    #[test]
+    fn test_package_key() {
+        assert_eq!(
+            parse_package_key(5, "/@babel/core/7.22.5_supports-color@5.5.0"),
+            Ok(("@babel/core".into(), "7.22.5".into())),
+        );
+        assert_eq!(
+            parse_package_key(6, "/@babel/core@7.22.5(supports-color@5.5.0)"),
+            Ok(("@babel/core".into(), "7.22.5".into())),
+        );
+        assert_eq!(
+            parse_package_key(9, "@babel/core@7.22.5"),
+            Ok(("@babel/core".into(), "7.22.5".into())),
+        );
+    }
+}
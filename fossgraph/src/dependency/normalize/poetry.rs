@@ -0,0 +1,302 @@
+use std::collections::HashSet;
+
+use crate::dependency::Dependency;
+
+use toml::{Table, Value};
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum Error {
+    #[error("Couldn't parse the lockfile.\n{message}")]
+    InvalidLockfileFormat { message: String },
+
+    #[error("Unsupported resolution: {resolution}")]
+    UnsupportedResolution { resolution: String },
+}
+
+impl From<toml::de::Error> for Error {
+    fn from(_error: toml::de::Error) -> Self {
+        Self::invalid_toml()
+    }
+}
+
+impl Error {
+    fn invalid_toml() -> Self {
+        Self::InvalidLockfileFormat {
+            message: "Not a valid TOML".into(),
+        }
+    }
+
+    fn invalid_format() -> Self {
+        Self::InvalidLockfileFormat {
+            message: "Malformed lockfile".into(),
+        }
+    }
+
+    fn invalid_source(name: &str) -> Self {
+        Self::InvalidLockfileFormat {
+            message: format!("Some package has unsupported source: {name}"),
+        }
+    }
+}
+
+// Package source rule:
+//
+// Packages from PyPI have no `[package.source]` table. Otherwise `type` is one of
+//
+// - `git`: `url` with `reference` (as written) and `resolved_reference` (commit)
+// - `url`: direct link to an archive
+// - `file`, `directory`: local path
+// - `legacy`: another package index (PEP 503 "simple" repository)
+
+pub(super) const PYPI_INDEXES: [&str; 2] = ["https://pypi.org/simple", "https://pypi.org/simple/"];
+
+/// GitHub archives are pinned to a ref
+/// (`https://github.com/<owner>/<name>/archive/<ref>.tar.gz`).
+pub(super) fn normalize_github_archive(url: &str) -> Option<Dependency> {
+    let substr = url.strip_prefix("https://github.com/")?;
+    let segments: Vec<&str> = substr.splitn(4, '/').collect();
+    let [owner, name, "archive", archive] = segments.as_slice() else {
+        return None;
+    };
+    let head = archive
+        .strip_suffix(".tar.gz")
+        .or_else(|| archive.strip_suffix(".zip"))?;
+    let head = head.strip_prefix("refs/tags/").unwrap_or(head);
+    let head = head.strip_prefix("refs/heads/").unwrap_or(head);
+    Some(Dependency::GitHub {
+        owner: (*owner).into(),
+        name: (*name).into(),
+        head: Some(head.into()),
+    })
+}
+
+fn normalize_single_package(package: &Table) -> Result<Dependency, Error> {
+    let name = package.get("name").and_then(Value::as_str);
+    let version = package.get("version").and_then(Value::as_str);
+    let (Some(name), Some(version)) = (name, version) else {
+        return Err(Error::invalid_format());
+    };
+    let Some(source) = package.get("source") else {
+        return Ok(Dependency::PyPI {
+            name: name.into(),
+            version: version.into(),
+        }
+        .canonicalize());
+    };
+
+    let source_type = source.get("type").and_then(Value::as_str);
+    let url = source.get("url").and_then(Value::as_str);
+    let (Some(source_type), Some(url)) = (source_type, url) else {
+        return Err(Error::invalid_source(name));
+    };
+    match source_type {
+        "git" => {
+            let head = ["resolved_reference", "reference"]
+                .iter()
+                .find_map(|key| source.get(*key).and_then(Value::as_str));
+            Ok(Dependency::Git {
+                url: url.into(),
+                head: head.map(String::from),
+            }
+            .canonicalize())
+        }
+        "legacy" if PYPI_INDEXES.contains(&url) => Ok(Dependency::PyPI {
+            name: name.into(),
+            version: version.into(),
+        }
+        .canonicalize()),
+        // arbitrary archive, local path and private index are not supported
+        "url" => normalize_github_archive(url).ok_or_else(|| Error::UnsupportedResolution {
+            resolution: url.into(),
+        }),
+        "file" | "directory" | "legacy" => Err(Error::UnsupportedResolution {
+            resolution: url.into(),
+        }),
+        _ => Err(Error::invalid_source(name)),
+    }
+}
+
+fn normalize_toml(table: Table) -> Result<HashSet<Dependency>, Error> {
+    let mut deps: HashSet<Dependency> = HashSet::new();
+
+    let Some(packages) = table.get("package") else {
+        return Ok(deps);
+    };
+    let Some(packages) = packages.as_array() else {
+        return Err(Error::invalid_format());
+    };
+    for package in packages {
+        let Some(package) = package.as_table() else {
+            return Err(Error::invalid_format());
+        };
+        match normalize_single_package(package) {
+            Ok(dependency) => {
+                deps.insert(dependency);
+            }
+            Err(Error::UnsupportedResolution { .. }) => {
+                // noop
+            }
+            Err(error) => {
+                return Err(error);
+            }
+        }
+    }
+    Ok(deps)
+}
+
+pub fn normalize(value: &str) -> Result<HashSet<Dependency>, Error> {
+    let table: Table = toml::from_str(value)?;
+    normalize_toml(table)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let lockfile = indoc! {r#"
+          # This file is automatically @generated by Poetry 1.5.1 and should not be changed by hand.
+
+          [[package]]
+          name = "karrot-ml"
+          version = "0.3.1"
+          description = ""
+          optional = false
+          python-versions = "^3.10"
+          files = []
+          develop = false
+
+          [package.source]
+          type = "git"
+          url = "https://github.com/daangn/karrot-ml.git"
+          reference = "main"
+          resolved_reference = "de5d97557a09ad61ae6ac48b1258b67d304660f0"
+
+          [[package]]
+          name = "local-utils"
+          version = "0.1.0"
+          description = ""
+          optional = false
+          python-versions = "^3.10"
+          files = []
+          develop = true
+
+          [package.source]
+          type = "directory"
+          url = "../local-utils"
+
+          [[package]]
+          name = "PyYAML"
+          version = "6.0"
+          description = "YAML parser and emitter for Python"
+          optional = false
+          python-versions = ">=3.6"
+          files = [
+              {file = "PyYAML-6.0.tar.gz", hash = "sha256:..."},
+          ]
+
+          [[package]]
+          name = "requests"
+          version = "2.31.0"
+          description = "Python HTTP for Humans."
+          optional = false
+          python-versions = ">=3.7"
+          files = [
+              {file = "requests-2.31.0-py3-none-any.whl", hash = "sha256:..."},
+          ]
+
+          [package.dependencies]
+          urllib3 = ">=1.21.1,<3"
+
+          [[package]]
+          name = "torch"
+          version = "2.0.1+cpu"
+          description = "Tensors and Dynamic neural networks in Python with strong GPU acceleration"
+          optional = false
+          python-versions = ">=3.8.0"
+          files = []
+
+          [package.source]
+          type = "legacy"
+          url = "https://download.pytorch.org/whl/cpu"
+          reference = "pytorch"
+
+          [[package]]
+          name = "typing_extensions"
+          version = "4.7.1"
+          description = "Backported and Experimental Type Hints for Python 3.7+"
+          optional = false
+          python-versions = ">=3.7"
+          files = []
+
+          [package.source]
+          type = "legacy"
+          url = "https://pypi.org/simple"
+          reference = "pypi-mirror"
+
+          [[package]]
+          name = "urllib3"
+          version = "2.0.3"
+          description = "HTTP library with thread-safe connection pooling, file post, and more."
+          optional = false
+          python-versions = ">=3.7"
+          files = []
+
+          [package.source]
+          type = "url"
+          url = "https://github.com/urllib3/urllib3/archive/refs/tags/2.0.3.tar.gz"
+
+          [metadata]
+          lock-version = "2.0"
+          python-versions = "^3.10"
+          content-hash = "..."
+        "#};
+
+        let result = normalize(lockfile).unwrap();
+        assert_eq!(
+            result,
+            HashSet::from([
+                Dependency::PyPI {
+                    name: "pyyaml".into(),
+                    version: "6.0".into(),
+                },
+                Dependency::PyPI {
+                    name: "requests".into(),
+                    version: "2.31.0".into(),
+                },
+                Dependency::PyPI {
+                    name: "typing-extensions".into(),
+                    version: "4.7.1".into(),
+                },
+                Dependency::GitHub {
+                    owner: "daangn".into(),
+                    name: "karrot-ml".into(),
+                    head: Some("de5d97557a09ad61ae6ac48b1258b67d304660f0".into()),
+                },
+                Dependency::GitHub {
+                    owner: "urllib3".into(),
+                    name: "urllib3".into(),
+                    head: Some("2.0.3".into()),
+                },
+            ]),
+        );
+    }
+
+    #[test]
+    fn test_canonicalize() {
+        let dependency = Dependency::PyPI {
+            name: "Zope.Interface__Extra".into(),
+            version: "1.0".into(),
+        };
+        assert_eq!(
+            dependency.canonicalize(),
+            Dependency::PyPI {
+                name: "zope-interface-extra".into(),
+                version: "1.0".into(),
+            },
+        );
+    }
+}
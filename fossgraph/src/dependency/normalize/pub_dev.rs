@@ -0,0 +1,269 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::dependency::Dependency;
+
+use serde_yaml::{Mapping, Value};
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum Error {
+    #[error("Couldn't parse the lockfile.\n{message}")]
+    InvalidLockfileFormat { message: String },
+
+    #[error("Unsupported resolution: {resolution}")]
+    UnsupportedResolution { resolution: String },
+}
+
+impl From<serde_yaml::Error> for Error {
+    fn from(_error: serde_yaml::Error) -> Self {
+        Self::invalid_yaml()
+    }
+}
+
+impl Error {
+    fn invalid_yaml() -> Self {
+        Self::InvalidLockfileFormat {
+            message: "Not a valid YAML".into(),
+        }
+    }
+
+    fn invalid_format() -> Self {
+        Self::InvalidLockfileFormat {
+            message: "Malformed lockfile".into(),
+        }
+    }
+
+    fn invalid_package(name: &str) -> Self {
+        Self::InvalidLockfileFormat {
+            message: format!("Some package has unsupported form: {name}"),
+        }
+    }
+}
+
+// Lockfile layout:
+//
+// `packages` maps package names to `{ dependency, description, source, version }`.
+//
+// "source" is one of
+//
+// - `hosted`: `description` has `name` and `url` of the package repository
+// - `git`: `description` has `url`, `ref` (as written) and `resolved-ref` (commit)
+// - `path`: `description` has a local `path`
+// - `sdk`: `description` is the name of SDK (e.g. `flutter`)
+//
+// "dependency" is one of `direct main`, `direct dev`, `direct overridden` and `transitive`.
+
+const PUB_DEV_URLS: [&str; 2] = ["https://pub.dev", "https://pub.dartlang.org"];
+
+/// How a package is depended on by the root package.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    DirectMain,
+    DirectDev,
+    DirectOverridden,
+    Transitive,
+}
+
+impl TryFrom<&str> for Kind {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "direct main" => Ok(Self::DirectMain),
+            "direct dev" => Ok(Self::DirectDev),
+            "direct overridden" => Ok(Self::DirectOverridden),
+            "transitive" => Ok(Self::Transitive),
+            _ => Err(Error::invalid_format()),
+        }
+    }
+}
+
+fn normalize_single_package(name: &str, package: &Mapping) -> Result<(Dependency, Kind), Error> {
+    let kind = package
+        .get("dependency")
+        .and_then(Value::as_str)
+        .ok_or_else(|| Error::invalid_package(name))?;
+    let kind = Kind::try_from(kind)?;
+    let source = package
+        .get("source")
+        .and_then(Value::as_str)
+        .ok_or_else(|| Error::invalid_package(name))?;
+    let description = package
+        .get("description")
+        .ok_or_else(|| Error::invalid_package(name))?;
+
+    let dependency = match source {
+        "hosted" => {
+            let url = description
+                .get("url")
+                .and_then(Value::as_str)
+                .ok_or_else(|| Error::invalid_package(name))?;
+            // private package repository is not supported
+            if !PUB_DEV_URLS.contains(&url.trim_end_matches('/')) {
+                return Err(Error::UnsupportedResolution {
+                    resolution: url.into(),
+                });
+            }
+            let version = package
+                .get("version")
+                .and_then(Value::as_str)
+                .ok_or_else(|| Error::invalid_package(name))?;
+            Dependency::Pub {
+                name: name.into(),
+                version: version.into(),
+            }
+        }
+        "git" => {
+            let url = description
+                .get("url")
+                .and_then(Value::as_str)
+                .ok_or_else(|| Error::invalid_package(name))?;
+            let head = ["resolved-ref", "ref"]
+                .iter()
+                .find_map(|key| description.get(*key).and_then(Value::as_str));
+            Dependency::Git {
+                url: url.into(),
+                head: head.map(String::from),
+            }
+            .canonicalize()
+        }
+        // local packages and SDKs are not supported
+        "path" | "sdk" => {
+            return Err(Error::UnsupportedResolution {
+                resolution: name.into(),
+            })
+        }
+        _ => return Err(Error::invalid_package(name)),
+    };
+    Ok((dependency, kind))
+}
+
+fn normalize_yaml(value: Value) -> Result<HashMap<Dependency, Kind>, Error> {
+    let mut deps: HashMap<Dependency, Kind> = HashMap::new();
+
+    let Some(packages) = value.get("packages") else {
+        return Ok(deps);
+    };
+    let Some(packages) = packages.as_mapping() else {
+        return Err(Error::invalid_format());
+    };
+    for (name, package) in packages {
+        let (Some(name), Some(package)) = (name.as_str(), package.as_mapping()) else {
+            return Err(Error::invalid_format());
+        };
+        match normalize_single_package(name, package) {
+            Ok((dependency, kind)) => {
+                deps.insert(dependency, kind);
+            }
+            Err(Error::UnsupportedResolution { .. }) => {
+                // noop
+            }
+            Err(error) => {
+                return Err(error);
+            }
+        }
+    }
+    Ok(deps)
+}
+
+pub fn normalize_with_kinds(value: &str) -> Result<HashMap<Dependency, Kind>, Error> {
+    let yaml: Value = serde_yaml::from_str(value)?;
+    normalize_yaml(yaml)
+}
+
+pub fn normalize(value: &str) -> Result<HashSet<Dependency>, Error> {
+    let deps = normalize_with_kinds(value)?;
+    Ok(deps.into_keys().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn test_normalize_with_kinds() {
+        let lockfile = indoc! {r#"
+          # Generated by pub
+          # See https://dart.dev/tools/pub/glossary#lockfile
+          packages:
+            async:
+              dependency: transitive
+              description:
+                name: async
+                sha256: "947bfcf187f74dbc5e146c9eb9c0f10c9f8b30743e341481c1e2ed3ecc18c20c"
+                url: "https://pub.dev"
+              source: hosted
+              version: "2.11.0"
+            flutter:
+              dependency: "direct main"
+              description: flutter
+              source: sdk
+              version: "0.0.0"
+            flutter_lints:
+              dependency: "direct dev"
+              description:
+                name: flutter_lints
+                sha256: "2118df84ef0c3ca93f96123a616ae8540879991b8b57af2f81b76a7ada49b2a4"
+                url: "https://pub.dev"
+              source: hosted
+              version: "2.0.2"
+            internal_api:
+              dependency: "direct main"
+              description:
+                name: internal_api
+                sha256: "..."
+                url: "https://pub.example.com"
+              source: hosted
+              version: "1.0.0"
+            karrot_ui:
+              dependency: "direct main"
+              description:
+                path: "."
+                ref: main
+                resolved-ref: de5d97557a09ad61ae6ac48b1258b67d304660f0
+                url: "https://github.com/daangn/karrot_ui.git"
+              source: git
+              version: "0.3.1"
+            local_utils:
+              dependency: "direct main"
+              description:
+                path: "../local_utils"
+                relative: true
+              source: path
+              version: "0.1.0"
+          sdks:
+            dart: ">=3.0.0 <4.0.0"
+            flutter: ">=3.10.0"
+        "#};
+
+        let result = normalize_with_kinds(lockfile).unwrap();
+        assert_eq!(
+            result,
+            HashMap::from([
+                (
+                    Dependency::Pub {
+                        name: "async".into(),
+                        version: "2.11.0".into(),
+                    },
+                    Kind::Transitive,
+                ),
+                (
+                    Dependency::Pub {
+                        name: "flutter_lints".into(),
+                        version: "2.0.2".into(),
+                    },
+                    Kind::DirectDev,
+                ),
+                (
+                    Dependency::GitHub {
+                        owner: "daangn".into(),
+                        name: "karrot_ui".into(),
+                        head: Some("de5d97557a09ad61ae6ac48b1258b67d304660f0".into()),
+                    },
+                    Kind::DirectMain,
+                ),
+            ]),
+        );
+    }
+}
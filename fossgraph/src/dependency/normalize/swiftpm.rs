@@ -0,0 +1,257 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::dependency::Dependency;
+
+use serde_json::{Map, Value};
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum Error {
+    #[error("Couldn't parse the lockfile.\n{message}")]
+    InvalidLockfileFormat { message: String },
+
+    #[error("Unsupported resolution: {resolution}")]
+    UnsupportedResolution { resolution: String },
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(_error: serde_json::Error) -> Self {
+        Self::invalid_json()
+    }
+}
+
+impl Error {
+    fn invalid_json() -> Self {
+        Self::InvalidLockfileFormat {
+            message: "Not a valid JSON".into(),
+        }
+    }
+
+    fn invalid_format() -> Self {
+        Self::InvalidLockfileFormat {
+            message: "Malformed lockfile".into(),
+        }
+    }
+
+    fn invalid_pin(identity: &str) -> Self {
+        Self::InvalidLockfileFormat {
+            message: format!("Some pin has no revision: {identity}"),
+        }
+    }
+}
+
+// Lockfile layout:
+//
+// version 1: `object.pins[]` of `{ package, repositoryURL, state }`
+// version 2, 3: `pins[]` of `{ identity, kind, location, state }`
+//
+// "state" has `revision` (commit hash) and either `version` or `branch`
+// depending on the requirement.
+//
+// "kind" is one of `remoteSourceControl`, `localSourceControl` and `registry`.
+// Version 1 only has remote source control pins.
+
+/// Requirement a pin was resolved from, alongside its revision.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct State {
+    pub version: Option<String>,
+    pub branch: Option<String>,
+}
+
+fn normalize_single_pin(pin: &Map<String, Value>) -> Result<(Dependency, State), Error> {
+    let identity = ["identity", "package"]
+        .iter()
+        .find_map(|key| pin.get(*key).and_then(Value::as_str))
+        .ok_or_else(Error::invalid_format)?;
+    let kind = pin
+        .get("kind")
+        .and_then(Value::as_str)
+        .unwrap_or("remoteSourceControl");
+    // local checkouts and registry packages are not supported
+    if kind != "remoteSourceControl" {
+        return Err(Error::UnsupportedResolution {
+            resolution: identity.into(),
+        });
+    }
+
+    let location = ["location", "repositoryURL"]
+        .iter()
+        .find_map(|key| pin.get(*key).and_then(Value::as_str))
+        .ok_or_else(Error::invalid_format)?;
+    let state = pin
+        .get("state")
+        .and_then(Value::as_object)
+        .ok_or_else(Error::invalid_format)?;
+    let revision = state
+        .get("revision")
+        .and_then(Value::as_str)
+        .ok_or_else(|| Error::invalid_pin(identity))?;
+
+    let dependency = Dependency::Git {
+        url: location.into(),
+        head: Some(revision.into()),
+    }
+    .canonicalize();
+    let state = State {
+        version: state
+            .get("version")
+            .and_then(Value::as_str)
+            .map(String::from),
+        branch: state
+            .get("branch")
+            .and_then(Value::as_str)
+            .map(String::from),
+    };
+    Ok((dependency, state))
+}
+
+fn normalize_json(value: Value) -> Result<HashMap<Dependency, State>, Error> {
+    let pins = value
+        .get("pins")
+        .or_else(|| value.get("object").and_then(|object| object.get("pins")))
+        .and_then(Value::as_array)
+        .ok_or_else(Error::invalid_format)?;
+    let mut deps: HashMap<Dependency, State> = HashMap::new();
+
+    for pin in pins {
+        let Some(pin) = pin.as_object() else {
+            return Err(Error::invalid_format());
+        };
+        match normalize_single_pin(pin) {
+            Ok((dependency, state)) => {
+                deps.insert(dependency, state);
+            }
+            Err(Error::UnsupportedResolution { .. }) => {
+                // noop
+            }
+            Err(error) => {
+                return Err(error);
+            }
+        }
+    }
+    Ok(deps)
+}
+
+pub fn normalize_with_states(value: &str) -> Result<HashMap<Dependency, State>, Error> {
+    let json: Value = serde_json::from_str(value)?;
+    normalize_json(json)
+}
+
+pub fn normalize(value: &str) -> Result<HashSet<Dependency>, Error> {
+    let deps = normalize_with_states(value)?;
+    Ok(deps.into_keys().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    fn alamofire() -> Dependency {
+        Dependency::GitHub {
+            owner: "Alamofire".into(),
+            name: "Alamofire".into(),
+            head: Some("bc268c28fb170f494de9e9927c371b8342979ece".into()),
+        }
+    }
+
+    fn internal_kit() -> Dependency {
+        Dependency::Git {
+            url: "git@git.example.com:ios/InternalKit.git".into(),
+            head: Some("0123456789abcdef0123456789abcdef01234567".into()),
+        }
+    }
+
+    #[test]
+    fn test_normalize_v1() {
+        let lockfile = indoc! {r#"
+          {
+            "object": {
+              "pins": [
+                {
+                  "package": "Alamofire",
+                  "repositoryURL": "https://github.com/Alamofire/Alamofire.git",
+                  "state": {
+                    "branch": null,
+                    "revision": "bc268c28fb170f494de9e9927c371b8342979ece",
+                    "version": "5.6.4"
+                  }
+                },
+                {
+                  "package": "InternalKit",
+                  "repositoryURL": "git@git.example.com:ios/InternalKit.git",
+                  "state": {
+                    "branch": "main",
+                    "revision": "0123456789abcdef0123456789abcdef01234567",
+                    "version": null
+                  }
+                }
+              ]
+            },
+            "version": 1
+          }
+        "#};
+
+        let result = normalize(lockfile).unwrap();
+        assert_eq!(result, HashSet::from([alamofire(), internal_kit()]));
+    }
+
+    #[test]
+    fn test_normalize_v2() {
+        let lockfile = indoc! {r#"
+          {
+            "originHash" : "...",
+            "pins" : [
+              {
+                "identity" : "alamofire",
+                "kind" : "remoteSourceControl",
+                "location" : "https://github.com/Alamofire/Alamofire.git",
+                "state" : {
+                  "revision" : "bc268c28fb170f494de9e9927c371b8342979ece",
+                  "version" : "5.6.4"
+                }
+              },
+              {
+                "identity" : "internalkit",
+                "kind" : "remoteSourceControl",
+                "location" : "git@git.example.com:ios/InternalKit.git",
+                "state" : {
+                  "branch" : "main",
+                  "revision" : "0123456789abcdef0123456789abcdef01234567"
+                }
+              },
+              {
+                "identity" : "localkit",
+                "kind" : "localSourceControl",
+                "location" : "/Users/karrot/LocalKit",
+                "state" : {
+                  "revision" : "89abcdef0123456789abcdef0123456789abcdef"
+                }
+              }
+            ],
+            "version" : 3
+          }
+        "#};
+
+        let result = normalize_with_states(lockfile).unwrap();
+        assert_eq!(
+            result,
+            HashMap::from([
+                (
+                    alamofire(),
+                    State {
+                        version: Some("5.6.4".into()),
+                        branch: None,
+                    }
+                ),
+                (
+                    internal_kit(),
+                    State {
+                        version: None,
+                        branch: Some("main".into()),
+                    }
+                ),
+            ]),
+        );
+    }
+}
@@ -0,0 +1,220 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::dependency::Dependency;
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum Error {
+    #[error("Couldn't parse the lockfile.\n{message}")]
+    InvalidLockfileFormat { message: String },
+}
+
+impl Error {
+    fn invalid_line(line_number: usize) -> Self {
+        Self::InvalidLockfileFormat {
+            message: format!("Unexpected syntax at line {line_number}"),
+        }
+    }
+
+    fn missing_version(source: &str) -> Self {
+        Self::InvalidLockfileFormat {
+            message: format!("Some provider has no version: {source}"),
+        }
+    }
+}
+
+// Lockfile syntax (.terraform.lock.hcl):
+//
+// ```
+// provider "<hostname>/<namespace>/<type>" {
+//   version     = "<version>"
+//   constraints = "<version constraints>"
+//   hashes = [
+//     "h1:<hash>",
+//     "zh:<hash>",
+//   ]
+// }
+// ```
+//
+// Terraform always writes it in this canonical form, so it's parsed by lines
+// rather than as a general HCL.
+
+/// Hashes (`h1:`, `zh:`) of the provider packages by each resolved provider.
+pub type Hashes = HashMap<Dependency, BTreeSet<String>>;
+
+/// Provider block being parsed.
+struct Provider<'a> {
+    source: &'a str,
+    version: Option<&'a str>,
+    hashes: BTreeSet<String>,
+    in_hashes: bool,
+}
+
+fn parse_string(value: &str) -> Option<&str> {
+    value.trim().strip_prefix('"')?.strip_suffix('"')
+}
+
+/// Parses the comma-separated strings of a list, with the trailing comma.
+fn parse_strings(value: &str, hashes: &mut BTreeSet<String>) -> Option<()> {
+    for item in value.split(',').filter(|item| !item.trim().is_empty()) {
+        hashes.insert(parse_string(item)?.into());
+    }
+    Some(())
+}
+
+pub fn normalize_with_hashes(value: &str) -> Result<Hashes, Error> {
+    let mut deps: Hashes = HashMap::new();
+
+    let mut provider: Option<Provider> = None;
+    for (index, line) in value.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with("//") {
+            continue;
+        }
+        let Some(current) = &mut provider else {
+            let source = line
+                .strip_prefix("provider ")
+                .and_then(|substr| substr.strip_suffix('{'))
+                .and_then(parse_string)
+                .ok_or_else(|| Error::invalid_line(index + 1))?;
+            provider = Some(Provider {
+                source,
+                version: None,
+                hashes: BTreeSet::new(),
+                in_hashes: false,
+            });
+            continue;
+        };
+        if current.in_hashes {
+            let items = match line.strip_suffix(']') {
+                Some(items) => {
+                    current.in_hashes = false;
+                    items
+                }
+                None => line,
+            };
+            parse_strings(items, &mut current.hashes)
+                .ok_or_else(|| Error::invalid_line(index + 1))?;
+        } else if line == "}" {
+            let Some(version) = current.version else {
+                return Err(Error::missing_version(current.source));
+            };
+            let dependency = Dependency::TerraformProvider {
+                source: current.source.into(),
+                version: version.into(),
+            };
+            deps.entry(dependency)
+                .or_default()
+                .append(&mut current.hashes);
+            provider = None;
+        } else if let Some((key, substr)) = line
+            .split_once('=')
+            .map(|(key, substr)| (key.trim(), substr.trim()))
+        {
+            match key {
+                "version" => {
+                    current.version =
+                        Some(parse_string(substr).ok_or_else(|| Error::invalid_line(index + 1))?);
+                }
+                "hashes" => {
+                    let Some(items) = substr.strip_prefix('[') else {
+                        return Err(Error::invalid_line(index + 1));
+                    };
+                    // the list is usually broken into lines
+                    let items = match items.strip_suffix(']') {
+                        Some(items) => items,
+                        None => {
+                            current.in_hashes = true;
+                            items
+                        }
+                    };
+                    parse_strings(items, &mut current.hashes)
+                        .ok_or_else(|| Error::invalid_line(index + 1))?;
+                }
+                _ => {}
+            }
+        }
+    }
+    if let Some(Provider { source, .. }) = provider {
+        return Err(Error::missing_version(source));
+    }
+    Ok(deps)
+}
+
+pub fn normalize(value: &str) -> Result<HashSet<Dependency>, Error> {
+    let deps = normalize_with_hashes(value)?;
+    Ok(deps.into_keys().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let lockfile = indoc! {r#"
+          # This file is maintained automatically by "terraform init".
+          # Manual edits may be lost in future updates.
+
+          provider "registry.terraform.io/hashicorp/aws" {
+            version     = "5.17.0"
+            constraints = "~> 5.0"
+            hashes = [
+              "h1:rplvK7UGP2FuzM44t2eRX+QYYPC0aK+q2vlZj5SkEUo=",
+              "zh:0087b9dd2c9c638fd63e527e5b9b70988008e263d480a199f180bf3a4b36b3fc",
+            ]
+          }
+
+          provider "registry.terraform.io/integrations/github" {
+            version     = "5.38.0"
+            constraints = "~> 5.0"
+            hashes = [
+              "h1:...",
+            ]
+          }
+        "#};
+
+        let result = normalize_with_hashes(lockfile).unwrap();
+        assert_eq!(
+            result,
+            HashMap::from([
+                (
+                    Dependency::TerraformProvider {
+                        source: "registry.terraform.io/hashicorp/aws".into(),
+                        version: "5.17.0".into(),
+                    },
+                    BTreeSet::from([
+                        "h1:rplvK7UGP2FuzM44t2eRX+QYYPC0aK+q2vlZj5SkEUo=".to_owned(),
+                        "zh:0087b9dd2c9c638fd63e527e5b9b70988008e263d480a199f180bf3a4b36b3fc"
+                            .to_owned(),
+                    ]),
+                ),
+                (
+                    Dependency::TerraformProvider {
+                        source: "registry.terraform.io/integrations/github".into(),
+                        version: "5.38.0".into(),
+                    },
+                    BTreeSet::from(["h1:...".to_owned()]),
+                ),
+            ]),
+        );
+    }
+
+    #[test]
+    fn test_normalize_invalid() {
+        let lockfile = indoc! {r#"
+          provider "registry.terraform.io/hashicorp/aws" {
+            constraints = "~> 5.0"
+            hashes = ["h1:..."]
+          }
+        "#};
+
+        assert_eq!(
+            normalize(lockfile),
+            Err(Error::missing_version(
+                "registry.terraform.io/hashicorp/aws"
+            )),
+        );
+    }
+}
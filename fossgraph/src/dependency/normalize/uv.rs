@@ -0,0 +1,258 @@
+use std::collections::HashSet;
+
+use crate::dependency::Dependency;
+
+use toml::{Table, Value};
+
+use super::poetry::{normalize_github_archive, PYPI_INDEXES};
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum Error {
+    #[error("Couldn't parse the lockfile.\n{message}")]
+    InvalidLockfileFormat { message: String },
+
+    #[error("Unsupported resolution: {resolution}")]
+    UnsupportedResolution { resolution: String },
+}
+
+impl From<toml::de::Error> for Error {
+    fn from(_error: toml::de::Error) -> Self {
+        Self::invalid_toml()
+    }
+}
+
+impl Error {
+    fn invalid_toml() -> Self {
+        Self::InvalidLockfileFormat {
+            message: "Not a valid TOML".into(),
+        }
+    }
+
+    fn invalid_format() -> Self {
+        Self::InvalidLockfileFormat {
+            message: "Malformed lockfile".into(),
+        }
+    }
+
+    fn invalid_source(name: &str) -> Self {
+        Self::InvalidLockfileFormat {
+            message: format!("Some package has unsupported source: {name}"),
+        }
+    }
+}
+
+// Package source rule:
+//
+// "source" is an inline table keyed by its type, one of
+//
+// - `registry`: URL of the package index
+// - `git`: `<url>(?<reference>)#<commit>`
+// - `url`: direct link to an archive
+// - `path`, `directory`, `editable`, `virtual`: local path (workspace members included)
+//
+// and may have other keys along with it (e.g. `subdirectory` of `url`).
+//
+// When the resolution forks by `resolution-markers`, the same package can appear
+// more than once with different versions. Each of them is a resolution on its own.
+
+const SOURCE_TYPES: [&str; 7] = [
+    "registry",
+    "git",
+    "url",
+    "path",
+    "directory",
+    "editable",
+    "virtual",
+];
+
+fn normalize_single_package(package: &Table) -> Result<Dependency, Error> {
+    let name = package
+        .get("name")
+        .and_then(Value::as_str)
+        .ok_or_else(Error::invalid_format)?;
+    let source = package
+        .get("source")
+        .and_then(Value::as_table)
+        .ok_or_else(|| Error::invalid_source(name))?;
+    let Some((source_type, location)) = SOURCE_TYPES
+        .iter()
+        .find_map(|source_type| Some((*source_type, source.get(*source_type)?)))
+    else {
+        return Err(Error::invalid_source(name));
+    };
+    let location = location
+        .as_str()
+        .ok_or_else(|| Error::invalid_source(name))?;
+
+    match source_type {
+        "registry" => {
+            // private index is not supported
+            if !PYPI_INDEXES.contains(&location) {
+                return Err(Error::UnsupportedResolution {
+                    resolution: location.into(),
+                });
+            }
+            let version = package
+                .get("version")
+                .and_then(Value::as_str)
+                .ok_or_else(Error::invalid_format)?;
+            Ok(Dependency::PyPI {
+                name: name.into(),
+                version: version.into(),
+            }
+            .canonicalize())
+        }
+        "git" => {
+            let Some((url, commit)) = location.split_once('#') else {
+                return Err(Error::invalid_source(name));
+            };
+            let (url, _reference) = url.split_once('?').unwrap_or((url, ""));
+            Ok(Dependency::Git {
+                url: url.into(),
+                head: Some(commit.into()),
+            }
+            .canonicalize())
+        }
+        // arbitrary archive is not supported
+        "url" => normalize_github_archive(location).ok_or_else(|| Error::UnsupportedResolution {
+            resolution: location.into(),
+        }),
+        // local path is not supported
+        "path" | "directory" | "editable" | "virtual" => Err(Error::UnsupportedResolution {
+            resolution: location.into(),
+        }),
+        _ => Err(Error::invalid_source(name)),
+    }
+}
+
+fn normalize_toml(table: Table) -> Result<HashSet<Dependency>, Error> {
+    let mut deps: HashSet<Dependency> = HashSet::new();
+
+    let Some(packages) = table.get("package") else {
+        return Ok(deps);
+    };
+    let Some(packages) = packages.as_array() else {
+        return Err(Error::invalid_format());
+    };
+    for package in packages {
+        let Some(package) = package.as_table() else {
+            return Err(Error::invalid_format());
+        };
+        match normalize_single_package(package) {
+            Ok(dependency) => {
+                deps.insert(dependency);
+            }
+            Err(Error::UnsupportedResolution { .. }) => {
+                // noop
+            }
+            Err(error) => {
+                return Err(error);
+            }
+        }
+    }
+    Ok(deps)
+}
+
+pub fn normalize(value: &str) -> Result<HashSet<Dependency>, Error> {
+    let table: Table = toml::from_str(value)?;
+    normalize_toml(table)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let lockfile = indoc! {r#"
+          version = 1
+          requires-python = ">=3.10"
+          resolution-markers = [
+              "python_full_version < '3.11'",
+              "python_full_version >= '3.11'",
+          ]
+
+          [[package]]
+          name = "example-app"
+          version = "0.1.0"
+          source = { editable = "." }
+          dependencies = [
+              { name = "karrot-ml" },
+              { name = "numpy", version = "1.26.4", source = { registry = "https://pypi.org/simple" }, marker = "python_full_version < '3.11'" },
+              { name = "numpy", version = "2.0.0", source = { registry = "https://pypi.org/simple" }, marker = "python_full_version >= '3.11'" },
+              { name = "private-lib" },
+              { name = "typing-extensions" },
+          ]
+
+          [[package]]
+          name = "karrot-ml"
+          version = "0.3.1"
+          source = { git = "https://github.com/daangn/karrot-ml.git?rev=main#de5d97557a09ad61ae6ac48b1258b67d304660f0" }
+
+          [[package]]
+          name = "karrot-vision"
+          version = "0.2.0"
+          source = { url = "https://github.com/daangn/karrot-ml/archive/0123456789abcdef0123456789abcdef01234567.zip", subdirectory = "vision" }
+
+          [[package]]
+          name = "numpy"
+          version = "1.26.4"
+          source = { registry = "https://pypi.org/simple" }
+          resolution-markers = [
+              "python_full_version < '3.11'",
+          ]
+          sdist = { url = "https://files.pythonhosted.org/packages/numpy-1.26.4.tar.gz", hash = "sha256:...", size = 15786129 }
+
+          [[package]]
+          name = "numpy"
+          version = "2.0.0"
+          source = { registry = "https://pypi.org/simple" }
+          resolution-markers = [
+              "python_full_version >= '3.11'",
+          ]
+          sdist = { url = "https://files.pythonhosted.org/packages/numpy-2.0.0.tar.gz", hash = "sha256:...", size = 18326228 }
+
+          [[package]]
+          name = "private-lib"
+          version = "1.0.0"
+          source = { registry = "https://pypi.example.com/simple" }
+
+          [[package]]
+          name = "typing-extensions"
+          version = "4.12.2"
+          source = { registry = "https://pypi.org/simple" }
+          sdist = { url = "https://files.pythonhosted.org/packages/typing_extensions-4.12.2.tar.gz", hash = "sha256:...", size = 85321 }
+        "#};
+
+        let result = normalize(lockfile).unwrap();
+        assert_eq!(
+            result,
+            HashSet::from([
+                Dependency::PyPI {
+                    name: "numpy".into(),
+                    version: "1.26.4".into(),
+                },
+                Dependency::PyPI {
+                    name: "numpy".into(),
+                    version: "2.0.0".into(),
+                },
+                Dependency::PyPI {
+                    name: "typing-extensions".into(),
+                    version: "4.12.2".into(),
+                },
+                Dependency::GitHub {
+                    owner: "daangn".into(),
+                    name: "karrot-ml".into(),
+                    head: Some("de5d97557a09ad61ae6ac48b1258b67d304660f0".into()),
+                },
+                Dependency::GitHub {
+                    owner: "daangn".into(),
+                    name: "karrot-ml".into(),
+                    head: Some("0123456789abcdef0123456789abcdef01234567".into()),
+                },
+            ]),
+        );
+    }
+}
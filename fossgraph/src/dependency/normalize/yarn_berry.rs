@@ -104,7 +104,7 @@ impl TryFrom<&str> for PackageDescriptor {
                 let Some((hostname, substr)) = url.split_once('/') else {
                     return Err(Error::invalid_descriptor(value));
                 };
-                vec![hostname, substr].join(":")
+                [hostname, substr].join(":")
             };
             Ok(Self::Git {
                 url,
@@ -176,10 +176,10 @@ impl PackageDescriptor {
         let bindings = captures.name("bindings").map(|m| {
             let dummy_url = "http://dummy?".to_owned() + m.as_str();
             let parsed = Url::parse(dummy_url.as_str()).unwrap();
-            return parsed
+            parsed
                 .query_pairs()
                 .into_owned()
-                .collect::<HashMap<String, String>>();
+                .collect::<HashMap<String, String>>()
         });
         Ok(PackageRange {
             protocol,
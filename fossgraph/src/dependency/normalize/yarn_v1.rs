@@ -0,0 +1,354 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::dependency::Dependency;
+
+use super::npm::{
+    is_git_resolution, is_registry_tarball, normalize_codeload_tarball, normalize_git_resolution,
+};
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum Error {
+    #[error("Couldn't parse the lockfile.\n{message}")]
+    InvalidLockfileFormat { message: String },
+
+    #[error("Unsupported resolution: {resolution}")]
+    UnsupportedResolution { resolution: String },
+}
+
+impl Error {
+    fn invalid_line(line_number: usize) -> Self {
+        Self::InvalidLockfileFormat {
+            message: format!("Unexpected syntax at line {line_number}"),
+        }
+    }
+
+    fn invalid_descriptor(descriptor: &str) -> Self {
+        Self::InvalidLockfileFormat {
+            message: format!("Some entry has unsupported descriptor: {descriptor}"),
+        }
+    }
+
+    fn missing_version(descriptor: &str) -> Self {
+        Self::InvalidLockfileFormat {
+            message: format!("Some entry has no version: {descriptor}"),
+        }
+    }
+}
+
+// Lockfile syntax:
+//
+// ```
+// # yarn lockfile v1
+//
+// "<descriptor>", "<descriptor>":
+//   version "<version>"
+//   resolved "<url>"
+//   integrity <hash>
+//   dependencies:
+//     <ident> "<range>"
+// ```
+//
+// Each entry starts with a comma-separated list of descriptors at column 0,
+// followed by fields indented by 2 spaces. Strings are quoted only if needed.
+// Nested maps (`dependencies`, `optionalDependencies`) are irrelevant to the resolution.
+
+#[derive(Debug, PartialEq, Eq)]
+struct Entry {
+    descriptors: Vec<String>,
+    fields: HashMap<String, String>,
+}
+
+fn unquote(value: &str) -> String {
+    match value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+    {
+        Some(value) => value.replace("\\\"", "\""),
+        None => value.to_owned(),
+    }
+}
+
+fn split_field(line: &str) -> Option<(String, String)> {
+    let (key, value) = if let Some(substr) = line.strip_prefix('"') {
+        let index = substr.find('"')? + 2;
+        line.split_at(index)
+    } else {
+        line.split_once(' ')?
+    };
+    Some((unquote(key), unquote(value.trim())))
+}
+
+fn parse(value: &str) -> Result<Vec<Entry>, Error> {
+    let mut entries: Vec<Entry> = Vec::new();
+
+    for (index, line) in value.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        match line.len() - trimmed.len() {
+            0 => {
+                let Some(header) = trimmed.strip_suffix(':') else {
+                    return Err(Error::invalid_line(index + 1));
+                };
+                entries.push(Entry {
+                    descriptors: header.split(", ").map(unquote).collect(),
+                    fields: HashMap::new(),
+                });
+            }
+            2 => {
+                let Some(entry) = entries.last_mut() else {
+                    return Err(Error::invalid_line(index + 1));
+                };
+                // start of nested map
+                if trimmed.ends_with(':') {
+                    continue;
+                }
+                let Some((key, value)) = split_field(trimmed) else {
+                    return Err(Error::invalid_line(index + 1));
+                };
+                entry.fields.insert(key, value);
+            }
+            _ => {
+                if entries.is_empty() {
+                    return Err(Error::invalid_line(index + 1));
+                }
+            }
+        }
+    }
+    Ok(entries)
+}
+
+fn split_range(descriptor: &str) -> Result<(&str, &str), Error> {
+    let index = if descriptor.starts_with('@') {
+        descriptor.match_indices('@').nth(1).map(|(index, _)| index)
+    } else {
+        descriptor.find('@')
+    };
+    let Some(index) = index else {
+        return Err(Error::invalid_descriptor(descriptor));
+    };
+    Ok((&descriptor[..index], &descriptor[index + 1..]))
+}
+
+fn normalize_entry(entry: &Entry) -> Result<Dependency, Error> {
+    let descriptor = entry.descriptors.first().unwrap();
+    let (ident, range) = split_range(descriptor)?;
+    // local packages have no `resolved`, and would be taken as from the registry
+    if ["file:", "link:", "portal:"]
+        .iter()
+        .any(|protocol| range.starts_with(protocol))
+    {
+        return Err(Error::UnsupportedResolution {
+            resolution: range.into(),
+        });
+    }
+    // aliased package (e.g. `string-width-cjs@npm:string-width@^4.2.0`)
+    let name = match range.strip_prefix("npm:").map(split_range) {
+        Some(Ok((name, _))) => name,
+        _ => ident,
+    };
+    let version = entry
+        .fields
+        .get("version")
+        .ok_or_else(|| Error::missing_version(descriptor))?;
+
+    let Some(resolved) = entry.fields.get("resolved") else {
+        return Ok(Dependency::Npm {
+            name: name.into(),
+            version: version.into(),
+        });
+    };
+    if let Some(dependency) = normalize_codeload_tarball(resolved) {
+        return Ok(dependency);
+    }
+    let (url, _hash) = resolved.split_once('#').unwrap_or((resolved, ""));
+    if is_git_resolution(resolved) || url.ends_with(".git") {
+        return Ok(normalize_git_resolution(resolved));
+    }
+    // private/custom registry, local tarball and `file:` links are not supported
+    if !is_registry_tarball(name, version, url) {
+        return Err(Error::UnsupportedResolution {
+            resolution: resolved.into(),
+        });
+    }
+    Ok(Dependency::Npm {
+        name: name.into(),
+        version: version.into(),
+    })
+}
+
+pub fn normalize(value: &str) -> Result<HashSet<Dependency>, Error> {
+    let mut deps: HashSet<Dependency> = HashSet::new();
+
+    for entry in parse(value)? {
+        match normalize_entry(&entry) {
+            Ok(dependency) => {
+                deps.insert(dependency);
+            }
+            Err(Error::UnsupportedResolution { .. }) => {
+                // noop
+            }
+            Err(error) => {
+                return Err(error);
+            }
+        }
+    }
+    Ok(deps)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let lockfile = indoc! {r#"
+          # THIS IS AN AUTOGENERATED FILE. DO NOT EDIT THIS FILE DIRECTLY.
+          # yarn lockfile v1
+
+
+          "@fortawesome/pro-solid-svg-icons@^6.4.0":
+            version "6.4.0"
+            resolved "https://npm.fontawesome.com/@fortawesome/pro-solid-svg-icons/-/6.4.0/pro-solid-svg-icons-6.4.0.tgz#f30e6573528355c6238ba96801bf2eaa9b0221b5"
+            integrity sha512-...
+
+          cjk-slug-github@daangn/cjk-slug:
+            version "0.3.1"
+            resolved "https://codeload.github.com/daangn/cjk-slug/tar.gz/de5d97557a09ad61ae6ac48b1258b67d304660f0"
+            dependencies:
+              normalize-cjk "^0.4.0"
+
+          "cjk-slug@git+ssh://git@github.com/daangn/cjk-slug.git":
+            version "0.3.1"
+            resolved "git+ssh://git@github.com/daangn/cjk-slug.git#de5d97557a09ad61ae6ac48b1258b67d304660f0"
+            dependencies:
+              normalize-cjk "^0.4.0"
+
+          "local-utils@file:../local-utils":
+            version "1.0.0"
+            dependencies:
+              lru-cache "^6.0.0"
+
+          "local-utils-link@link:../local-utils":
+            version "0.0.0"
+
+          lru-cache@^6.0.0:
+            version "6.0.0"
+            resolved "https://registry.yarnpkg.com/lru-cache/-/lru-cache-6.0.0.tgz#6d6fe6570ebd96aaf90fcad1dafa3b2566db3a94"
+            integrity sha512-...
+            dependencies:
+              yallist "^4.0.0"
+
+          normalize-cjk@^0.4.0:
+            version "0.4.0"
+            resolved "https://registry.yarnpkg.com/normalize-cjk/-/normalize-cjk-0.4.0.tgz#424059f5b226df99609843788ba80d7727ed0d16"
+            integrity sha512-...
+
+          "private-lib@git+ssh://git@git.example.com/team/private-lib.git":
+            version "1.0.0"
+            resolved "git+ssh://git@git.example.com/team/private-lib.git#0123456789abcdef0123456789abcdef01234567"
+
+          "semver@^7.5.0", semver@^7.5.1:
+            version "7.5.1"
+            resolved "https://registry.yarnpkg.com/semver/-/semver-7.5.1.tgz#c90c4d631cf74720e46b21c1d37ea07edfab91ec"
+            integrity sha512-...
+            dependencies:
+              lru-cache "^6.0.0"
+
+          "string-width-cjs@npm:string-width@^4.2.0":
+            version "4.2.3"
+            resolved "https://registry.yarnpkg.com/string-width/-/string-width-4.2.3.tgz#269c7117d27b05ad2e536830a8ec895ef9c6d010"
+            integrity sha512-...
+
+          yallist@^4.0.0:
+            version "4.0.0"
+            resolved "https://registry.yarnpkg.com/yallist/-/yallist-4.0.0.tgz#9bb92790d9c0effec63be73519e11a35019a3a72"
+            integrity sha512-...
+        "#};
+
+        let result = normalize(lockfile).unwrap();
+        assert_eq!(
+            result,
+            HashSet::from([
+                Dependency::Npm {
+                    name: "normalize-cjk".into(),
+                    version: "0.4.0".into(),
+                },
+                Dependency::Npm {
+                    name: "semver".into(),
+                    version: "7.5.1".into(),
+                },
+                Dependency::Npm {
+                    name: "lru-cache".into(),
+                    version: "6.0.0".into(),
+                },
+                Dependency::Npm {
+                    name: "string-width".into(),
+                    version: "4.2.3".into(),
+                },
+                Dependency::Npm {
+                    name: "yallist".into(),
+                    version: "4.0.0".into(),
+                },
+                Dependency::GitHub {
+                    owner: "daangn".into(),
+                    name: "cjk-slug".into(),
+                    head: Some("de5d97557a09ad61ae6ac48b1258b67d304660f0".into()),
+                },
+                Dependency::Git {
+                    url: "git@git.example.com:team/private-lib.git".into(),
+                    head: Some("0123456789abcdef0123456789abcdef01234567".into()),
+                },
+            ]),
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        let lockfile = indoc! {r#"
+          # yarn lockfile v1
+
+
+          "@babel/code-frame@^7.0.0", "@babel/code-frame@^7.10.4":
+            version "7.12.13"
+            resolved "https://registry.yarnpkg.com/@babel/code-frame/-/code-frame-7.12.13.tgz"
+            integrity sha512-...
+            dependencies:
+              "@babel/highlight" "^7.12.13"
+        "#};
+
+        let result = parse(lockfile).unwrap();
+        assert_eq!(
+            result,
+            vec![Entry {
+                descriptors: vec![
+                    "@babel/code-frame@^7.0.0".into(),
+                    "@babel/code-frame@^7.10.4".into(),
+                ],
+                fields: HashMap::from([
+                    ("version".to_owned(), "7.12.13".to_owned()),
+                    (
+                        "resolved".to_owned(),
+                        "https://registry.yarnpkg.com/@babel/code-frame/-/code-frame-7.12.13.tgz"
+                            .to_owned()
+                    ),
+                    ("integrity".to_owned(), "sha512-...".to_owned()),
+                ]),
+            }],
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        let lockfile = indoc! {r#"
+          # yarn lockfile v1
+
+            version "7.12.13"
+        "#};
+
+        assert_eq!(parse(lockfile), Err(Error::invalid_line(3)));
+    }
+}
@@ -0,0 +1,166 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use ignore::WalkBuilder;
+
+use crate::dependency::normalize::{self, detect_and_normalize, Format};
+use crate::dependency::Dependency;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Couldn't walk the directory.\n{0}")]
+    Walk(#[from] ignore::Error),
+}
+
+// Directories of installed packages, which have their own lockfiles
+// but aren't part of the project.
+const SKIPPED_DIRS: [&str; 4] = [".git", "node_modules", "Pods", "vendor"];
+
+/// Normalization result of a single lockfile.
+#[derive(Debug)]
+pub struct Report {
+    /// Relative to the scanned root.
+    pub path: PathBuf,
+    pub result: Result<(Format, HashSet<Dependency>), normalize::Error>,
+}
+
+#[derive(Debug, Default)]
+pub struct Scan {
+    /// Sorted by path.
+    pub reports: Vec<Report>,
+    /// Union of the dependencies of successfully normalized lockfiles.
+    pub dependencies: HashSet<Dependency>,
+}
+
+/// Finds every lockfile under the root and normalizes them.
+///
+/// `.gitignore` is respected even outside of a git repository.
+/// A lockfile failing to normalize doesn't stop the scan, but is reported.
+pub fn scan(root: impl AsRef<Path>) -> Result<Scan, Error> {
+    let root = root.as_ref();
+    let mut scan = Scan::default();
+
+    let walker = WalkBuilder::new(root)
+        .hidden(false)
+        .require_git(false)
+        .sort_by_file_name(|a, b| a.cmp(b))
+        .filter_entry(|entry| {
+            let is_dir = entry
+                .file_type()
+                .is_some_and(|file_type| file_type.is_dir());
+            !(is_dir && SKIPPED_DIRS.iter().any(|dir| entry.file_name() == *dir))
+        })
+        .build();
+    for entry in walker {
+        let entry = entry?;
+        if !entry
+            .file_type()
+            .is_some_and(|file_type| file_type.is_file())
+        {
+            continue;
+        }
+        let path = entry.path();
+        if !Format::is_lockfile(path) {
+            continue;
+        }
+        let result = detect_and_normalize(path);
+        if let Ok((_format, deps)) = &result {
+            scan.dependencies.extend(deps.iter().cloned());
+        }
+        scan.reports.push(Report {
+            path: path.strip_prefix(root).unwrap_or(path).to_path_buf(),
+            result,
+        });
+    }
+    Ok(scan)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn test_scan() {
+        let root = std::env::temp_dir().join(format!("fossgraph-scan-{}", std::process::id()));
+        let files = [
+            (".gitignore", "/build\n"),
+            (
+                "Cargo.lock",
+                indoc! {r#"
+                  version = 3
+
+                  [[package]]
+                  name = "cfg-if"
+                  version = "1.0.0"
+                  source = "registry+https://github.com/rust-lang/crates.io-index"
+                "#},
+            ),
+            (
+                "apps/web/yarn.lock",
+                indoc! {r#"
+                  # yarn lockfile v1
+
+                  cfg-if@^1.0.0:
+                    version "1.0.0"
+                    resolved "https://registry.yarnpkg.com/cfg-if/-/cfg-if-1.0.0.tgz"
+                "#},
+            ),
+            ("apps/web/node_modules/a/yarn.lock", "# yarn lockfile v1\n"),
+            ("apps/broken/yarn.lock", "not a lockfile"),
+            ("build/go.sum", "github.com/google/uuid v1.3.0 h1:...\n"),
+            (
+                "services/api/go.sum",
+                "github.com/google/uuid v1.3.0 h1:...\ngithub.com/google/uuid v1.3.0/go.mod h1:...\n",
+            ),
+            ("services/api/vendor/go.sum", "github.com/a/b v1.0.0 h1:...\n"),
+        ];
+        for (path, content) in files {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+
+        let result = scan(&root);
+        fs::remove_dir_all(&root).unwrap();
+        let result = result.unwrap();
+
+        let reports: Vec<(PathBuf, Option<Format>)> = result
+            .reports
+            .iter()
+            .map(|report| {
+                let format = report.result.as_ref().ok().map(|(format, _)| *format);
+                (report.path.clone(), format)
+            })
+            .collect();
+        assert_eq!(
+            reports,
+            vec![
+                (PathBuf::from("Cargo.lock"), Some(Format::Cargo)),
+                (PathBuf::from("apps/broken/yarn.lock"), None),
+                (PathBuf::from("apps/web/yarn.lock"), Some(Format::YarnV1)),
+                (PathBuf::from("services/api/go.sum"), Some(Format::Go)),
+            ],
+        );
+        assert_eq!(
+            result.dependencies,
+            HashSet::from([
+                Dependency::Cargo {
+                    name: "cfg-if".into(),
+                    version: "1.0.0".into(),
+                },
+                Dependency::Npm {
+                    name: "cfg-if".into(),
+                    version: "1.0.0".into(),
+                },
+                Dependency::GoModule {
+                    path: "github.com/google/uuid".into(),
+                    version: "v1.3.0".into(),
+                },
+            ]),
+        );
+    }
+}